@@ -1,7 +1,65 @@
 //! A module that handles the logic of storing accounts while the app is running
+mod expiry;
+mod metadata;
+
+pub use expiry::{describe_expiry, expiring, Expiry};
+pub use metadata::Metadata;
 
 use magic_crypt::{MagicCrypt256, MagicCryptTrait};
 
+use crate::generator::DerivationOptions;
+use crate::policy::Policy;
+use crate::rotation::{RotationScript, ScriptRun};
+use crate::time;
+
+/// The metadata keys the times the account was created, last modified, had its password changed
+/// and was last used are stored under
+const CREATED: &str = "created";
+const MODIFIED: &str = "modified";
+const PASSWORD_CHANGED: &str = "password_changed";
+const LAST_USED: &str = "last_used";
+/// The metadata keys the options a password is derived with are stored under
+const DERIVE_SITE: &str = "derive.site";
+const DERIVE_LOGIN: &str = "derive.login";
+const DERIVE_COUNTER: &str = "derive.counter";
+const DERIVE_RULES: &str = "derive.rules";
+/// The metadata key the rules the site enforces on passwords are stored under
+const POLICY: &str = "policy";
+/// The metadata keys the site's address, the tags and the notes are stored under
+const URL: &str = "url";
+const TAGS: &str = "tags";
+const NOTES: &str = "notes";
+/// The metadata keys a rotation is stored under. A rotation either has a pending password, or
+/// for derived passwords the counter the new password is derived with
+const ROTATION_PASSWORD: &str = "rotation.password";
+const ROTATION_COUNTER: &str = "rotation.counter";
+const ROTATION_STARTED: &str = "rotation.started";
+/// The metadata keys a rotation script and the outcome of its last run are stored under
+const SCRIPT_COMMAND: &str = "rotation.command";
+const SCRIPT_INTERVAL: &str = "rotation.interval";
+const SCRIPT_LAST_RUN: &str = "rotation.last_run";
+const SCRIPT_RESULT: &str = "rotation.last_result";
+/// The metadata keys a password's expiry date, or how many days it lasts, are stored under
+const EXPIRES_ON: &str = "expires";
+const EXPIRES_EVERY: &str = "expires.every";
+/// The metadata key the time a temporary account deletes itself is stored under
+const DELETES_ON: &str = "deletes_on";
+/// The metadata key the time an account was moved to the trash is stored under
+const TRASHED: &str = "trashed";
+/// The prefix of the metadata keys previous passwords are stored under, numbered from the newest
+const HISTORY_PREFIX: &str = "history.";
+/// How many previous passwords are kept
+pub const MAX_HISTORY: usize = 10;
+
+/// A struct that stores a password an account used to have
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PastPassword {
+    /// The old password
+    pub password: String,
+    /// When it was replaced, in seconds since the Unix epoch
+    pub replaced: u64,
+}
+
 #[derive(Debug, Clone)]
 /// A struct that stores the details of an account stored in the password manager
 pub struct Account {
@@ -9,6 +67,7 @@ pub struct Account {
     username: Option<String>,
     email: Option<String>,
     password: String,
+    metadata: Metadata,
 }
 
 impl Account {
@@ -28,6 +87,9 @@ impl Account {
     ///
     /// * `new_label` - What the accounts new label should be
     pub fn set_label(&mut self, new_label: String) {
+        if new_label != self.label {
+            self.touch();
+        }
         self.label = new_label;
     }
 
@@ -47,6 +109,9 @@ impl Account {
     ///
     /// * `new_email` - What the accounts new email should be
     pub fn set_email(&mut self, new_email: Option<String>) {
+        if new_email != self.email {
+            self.touch();
+        }
         self.email = new_email;
     }
 
@@ -66,23 +131,682 @@ impl Account {
     ///
     /// * `new_username` - What the accounts new username should be
     pub fn set_username(&mut self, new_username: Option<String>) {
+        if new_username != self.username {
+            self.touch();
+        }
         self.username = new_username;
     }
 
+    /// Returns the address of the Account's site
+    ///
+    /// # Returns
+    ///
+    /// `None` if there is no URL attached to the account, otherwise a clone of the URL
+    pub fn url(&self) -> Option<String> {
+        self.metadata.get(URL).map(str::to_string)
+    }
+
+    /// Sets the address of the Account's site
+    ///
+    /// # Arguments
+    ///
+    /// * `new_url` - What the accounts new URL should be
+    pub fn set_url(&mut self, new_url: Option<String>) {
+        self.set_detail(URL, new_url);
+    }
+
+    /// Returns the tags the Account is grouped by, in the order they were added
+    pub fn tags(&self) -> Vec<String> {
+        self.metadata
+            .get(TAGS)
+            .map(|tags| tags.split(',').map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    /// Sets the tags the Account is grouped by. Tags are trimmed and blank ones are dropped
+    ///
+    /// # Arguments
+    ///
+    /// * `new_tags` - What the accounts new tags should be
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::account::Account;
+    /// let mut account = Account::builder().build();
+    /// account.set_tags(vec![" work".to_string(), "".to_string(), "shared ".to_string()]);
+    /// assert_eq!(account.tags(), vec!["work", "shared"]);
+    /// ```
+    pub fn set_tags(&mut self, new_tags: Vec<String>) {
+        let tags: Vec<&str> = new_tags
+            .iter()
+            .map(|tag| tag.trim())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        let tags = match tags.is_empty() {
+            true => None,
+            false => Some(tags.join(",")),
+        };
+        self.set_detail(TAGS, tags);
+    }
+
+    /// Returns a copy of the Account's notes
+    ///
+    /// # Returns
+    ///
+    /// `None` if there are no notes attached to the account, otherwise a clone of the notes
+    pub fn notes(&self) -> Option<String> {
+        self.metadata.get(NOTES).map(str::to_string)
+    }
+
+    /// Sets the Account's notes
+    ///
+    /// # Arguments
+    ///
+    /// * `new_notes` - What the accounts new notes should be
+    pub fn set_notes(&mut self, new_notes: Option<String>) {
+        self.set_detail(NOTES, new_notes);
+    }
+
+    /// Sets or removes a detail stored in the Account's metadata, recording the change
+    fn set_detail(&mut self, key: &str, value: Option<String>) {
+        if value.as_deref() != self.metadata.get(key) {
+            self.touch();
+        }
+        match value {
+            Some(value) => self.metadata.set(key, value),
+            None => self.metadata.remove(key),
+        }
+    }
+
     /// Returns a clone of the password attached to the account
     pub fn password(&self) -> String {
         self.password.clone()
     }
 
-    /// Sets the Accounts password to the value of new_password
+    /// Sets the Accounts password to the value of new_password, recording when it changed and
+    /// keeping the old password in the history
     ///
     /// # Arguments
     ///
     /// * `new_password` - What the accounts new password should be
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::account::Account;
+    /// let mut account = Account::builder().password("first").build();
+    /// account.set_password("second".to_string());
+    /// account.set_password("third".to_string());
+    ///
+    /// let history: Vec<String> = account.history().into_iter().map(|x| x.password).collect();
+    /// assert_eq!(history, vec!["second", "first"]);
+    /// ```
     pub fn set_password(&mut self, new_password: String) {
+        if new_password != self.password {
+            let now = time::now();
+            self.metadata.set(PASSWORD_CHANGED, now);
+            self.metadata.set(MODIFIED, now);
+
+            if !self.password.is_empty() {
+                self.push_history(self.password.clone(), now);
+            }
+        }
         self.password = new_password;
     }
 
+    /// Returns when the Account was created
+    ///
+    /// # Returns
+    ///
+    /// `None` if the Account was created before times started being recorded, otherwise the time
+    /// in seconds since the Unix epoch
+    pub fn created(&self) -> Option<u64> {
+        self.metadata.get(CREATED)?.parse().ok()
+    }
+
+    /// Returns when the Account was last changed, in seconds since the Unix epoch
+    pub fn modified(&self) -> Option<u64> {
+        self.metadata.get(MODIFIED)?.parse().ok()
+    }
+
+    /// Returns when a detail of the Account was last yanked, in seconds since the Unix epoch
+    pub fn last_used(&self) -> Option<u64> {
+        self.metadata.get(LAST_USED)?.parse().ok()
+    }
+
+    /// Records that a detail of the Account was just used. Using an Account doesn't count as
+    /// modifying it
+    pub fn record_use(&mut self) {
+        self.metadata.set(LAST_USED, time::now());
+    }
+
+    /// Records that the Account was just modified
+    fn touch(&mut self) {
+        self.metadata.set(MODIFIED, time::now());
+    }
+
+    /// Returns the passwords the Account used to have, newest first
+    pub fn history(&self) -> Vec<PastPassword> {
+        (0..MAX_HISTORY)
+            .map_while(|position| {
+                let entry = self
+                    .metadata
+                    .get(&format!("{}{}", HISTORY_PREFIX, position))?;
+                // Times are only digits, so the password is everything after the first `:`
+                let (replaced, password) = entry.split_once(':')?;
+                Some(PastPassword {
+                    password: password.to_string(),
+                    replaced: replaced.parse().ok()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Adds a password the Account no longer uses to the front of its history
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password that was replaced
+    /// * `replaced` - When it was replaced, in seconds since the Unix epoch
+    fn push_history(&mut self, password: String, replaced: u64) {
+        let mut history = self.history();
+        history.insert(0, PastPassword { password, replaced });
+        self.set_history(history);
+    }
+
+    /// Replaces the Account's history, dropping the oldest passwords beyond the limit
+    fn set_history(&mut self, history: Vec<PastPassword>) {
+        for position in 0..MAX_HISTORY {
+            self.metadata
+                .remove(&format!("{}{}", HISTORY_PREFIX, position));
+        }
+        for (position, past) in history.into_iter().take(MAX_HISTORY).enumerate() {
+            self.metadata.set(
+                &format!("{}{}", HISTORY_PREFIX, position),
+                format!("{}:{}", past.replaced, past.password),
+            );
+        }
+    }
+
+    /// Makes a previous password the Account's password again. The current password takes its
+    /// place in the history, and a derived password stops being derived
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The position of the password in the history, newest first
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::account::Account;
+    /// let mut account = Account::builder().password("old").build();
+    /// account.set_password("new".to_string());
+    ///
+    /// account.restore_password(0);
+    /// assert_eq!(account.password(), "old");
+    /// assert_eq!(account.history()[0].password, "new");
+    /// assert_eq!(account.history().len(), 1);
+    /// ```
+    pub fn restore_password(&mut self, position: usize) {
+        let mut history = self.history();
+        if position >= history.len() {
+            return;
+        }
+        let restored = history.remove(position);
+        self.set_history(history);
+
+        self.set_derivation(None);
+        self.set_password(restored.password);
+    }
+
+    /// Returns when the Account's password last changed
+    ///
+    /// # Returns
+    ///
+    /// `None` if the password hasn't changed since times started being recorded, otherwise the
+    /// time in seconds since the Unix epoch
+    pub fn password_changed(&self) -> Option<u64> {
+        self.metadata.get(PASSWORD_CHANGED)?.parse().ok()
+    }
+
+    /// Returns the Account's metadata, which holds any details beyond the main fields
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Returns the options the Account's password is derived with
+    ///
+    /// # Returns
+    ///
+    /// `None` if the Account stores its password, otherwise the derivation options
+    pub fn derivation(&self) -> Option<DerivationOptions> {
+        let site = self.metadata.get(DERIVE_SITE)?;
+        let login = self.metadata.get(DERIVE_LOGIN)?;
+        let counter = self.metadata.get(DERIVE_COUNTER)?.parse().ok()?;
+
+        let mut options = DerivationOptions::new(site, login);
+        options.set_counter(counter);
+        options.with_rules(self.metadata.get(DERIVE_RULES)?)
+    }
+
+    /// Sets the options the Account's password is derived with. Derived passwords aren't stored,
+    /// so the stored password is moved to the history
+    ///
+    /// # Arguments
+    ///
+    /// * `derivation` - The derivation options, or `None` to stop deriving the password
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::account::Account;
+    /// # use password_manager::generator::DerivationOptions;
+    /// let mut account = Account::builder().password("stored").build();
+    /// account.set_derivation(Some(DerivationOptions::new("example.org", "me")));
+    ///
+    /// assert_eq!(account.password(), "");
+    /// assert_eq!(account.history()[0].password, "stored");
+    /// ```
+    pub fn set_derivation(&mut self, derivation: Option<DerivationOptions>) {
+        match derivation {
+            Some(derivation) => {
+                if self.derivation().as_ref() != Some(&derivation) {
+                    self.metadata.set(PASSWORD_CHANGED, time::now());
+                    self.touch();
+                }
+                if !self.password.is_empty() {
+                    self.push_history(self.password.clone(), time::now());
+                }
+                // A stored pending password doesn't apply once the password is derived
+                if self.metadata.get(ROTATION_PASSWORD).is_some() {
+                    self.cancel_rotation();
+                }
+                self.metadata.set(DERIVE_SITE, derivation.site());
+                self.metadata.set(DERIVE_LOGIN, derivation.login());
+                self.metadata.set(DERIVE_COUNTER, derivation.counter());
+                self.metadata.set(DERIVE_RULES, derivation.to_rules());
+                self.password = String::new();
+            }
+            None => {
+                if self.derivation().is_some() {
+                    self.touch();
+                }
+                for key in [DERIVE_SITE, DERIVE_LOGIN, DERIVE_COUNTER, DERIVE_RULES] {
+                    self.metadata.remove(key);
+                }
+            }
+        }
+    }
+
+    /// Stops deriving the Account's password, storing the derived password instead so the
+    /// account keeps working
+    ///
+    /// # Arguments
+    ///
+    /// * `master` - The master password the password is derived from
+    ///
+    /// # Returns
+    ///
+    /// `true` if a derived password was stored, or an error if it couldn't be derived
+    pub fn store_derived_password(&mut self, master: &str) -> Result<bool, String> {
+        let Some(derivation) = self.derivation() else {
+            return Ok(false);
+        };
+
+        // The password stays the same, so it isn't recorded as changed
+        self.password = derivation.derive(master.trim())?;
+
+        // A rotation to the next counter can't be derived once the password is stored, so the
+        // pending password is stored too
+        if self.metadata.get(ROTATION_COUNTER).is_some() {
+            match self.pending_password(master) {
+                Some(Ok(pending)) => {
+                    self.metadata.remove(ROTATION_COUNTER);
+                    self.metadata.set(ROTATION_PASSWORD, pending);
+                }
+                _ => self.cancel_rotation(),
+            }
+        }
+
+        self.set_derivation(None);
+        Ok(true)
+    }
+
+    /// Starts rotating the Account's password. The new password is kept as pending, next to the
+    /// current one, until the rotation is confirmed or cancelled. Derived passwords rotate to the
+    /// next counter, so the new password is ignored
+    ///
+    /// # Arguments
+    ///
+    /// * `new_password` - The password to rotate to
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::account::Account;
+    /// let mut account = Account::builder().password("old").build();
+    ///
+    /// account.start_rotation("new".to_string());
+    /// assert_eq!(account.password(), "old");
+    /// assert_eq!(account.pending_password("master"), Some(Ok("new".to_string())));
+    ///
+    /// account.confirm_rotation("master");
+    /// assert_eq!(account.password(), "new");
+    /// assert_eq!(account.pending_password("master"), None);
+    /// ```
+    pub fn start_rotation(&mut self, new_password: String) {
+        match self.derivation() {
+            Some(derivation) => {
+                self.metadata.remove(ROTATION_PASSWORD);
+                self.metadata
+                    .set(ROTATION_COUNTER, derivation.counter() + 1);
+            }
+            None => {
+                self.metadata.remove(ROTATION_COUNTER);
+                self.metadata.set(ROTATION_PASSWORD, new_password);
+            }
+        }
+        self.metadata.set(ROTATION_STARTED, time::now());
+        self.touch();
+    }
+
+    /// Returns whether the Account's password is being rotated
+    pub fn is_rotating(&self) -> bool {
+        self.metadata.get(ROTATION_STARTED).is_some()
+    }
+
+    /// Returns when the Account's current rotation started, in seconds since the Unix epoch
+    pub fn rotation_started(&self) -> Option<u64> {
+        self.metadata.get(ROTATION_STARTED)?.parse().ok()
+    }
+
+    /// Returns the password the Account is being rotated to
+    ///
+    /// # Arguments
+    ///
+    /// * `master` - The master password derived passwords come from
+    ///
+    /// # Returns
+    ///
+    /// `None` if the password isn't being rotated, otherwise the pending password or why it
+    /// couldn't be derived
+    pub fn pending_password(&self, master: &str) -> Option<Result<String, String>> {
+        if let Some(password) = self.metadata.get(ROTATION_PASSWORD) {
+            return Some(Ok(password.to_string()));
+        }
+
+        let counter = self.metadata.get(ROTATION_COUNTER)?.parse().ok()?;
+        let mut derivation = self.derivation()?;
+        derivation.set_counter(counter);
+        Some(derivation.derive(master.trim()))
+    }
+
+    /// Makes the pending password the Account's password, once it has been changed on the site.
+    /// The password it replaces goes into the history
+    ///
+    /// # Arguments
+    ///
+    /// * `master` - The master password derived passwords come from
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::account::Account;
+    /// # use password_manager::generator::DerivationOptions;
+    /// let mut account = Account::builder().build();
+    /// account.set_derivation(Some(DerivationOptions::new("example.org", "me")));
+    /// let old = account.resolve_password("master").unwrap();
+    ///
+    /// account.start_rotation(String::new());
+    /// account.confirm_rotation("master");
+    /// assert_eq!(account.history()[0].password, old);
+    /// ```
+    pub fn confirm_rotation(&mut self, master: &str) {
+        if let Some(password) = self.metadata.get(ROTATION_PASSWORD) {
+            self.set_password(password.to_string());
+        }
+        let counter = self
+            .metadata
+            .get(ROTATION_COUNTER)
+            .and_then(|x| x.parse().ok());
+        if let (Some(counter), Some(mut derivation)) = (counter, self.derivation()) {
+            // Derived passwords aren't stored, so the outgoing one is derived to keep it
+            if let Ok(old_password) = derivation.derive(master.trim()) {
+                self.push_history(old_password, time::now());
+            }
+            derivation.set_counter(counter);
+            self.set_derivation(Some(derivation));
+        }
+        self.cancel_rotation();
+    }
+
+    /// Stops rotating the Account's password, keeping the current one
+    pub fn cancel_rotation(&mut self) {
+        if self.is_rotating() {
+            self.touch();
+        }
+        for key in [ROTATION_PASSWORD, ROTATION_COUNTER, ROTATION_STARTED] {
+            self.metadata.remove(key);
+        }
+    }
+
+    /// Returns the script that changes the Account's password on the service it's for
+    ///
+    /// # Returns
+    ///
+    /// `None` if the Account's password is only rotated by hand, otherwise the script
+    pub fn rotation_script(&self) -> Option<RotationScript> {
+        let command = self.metadata.get(SCRIPT_COMMAND)?;
+        let interval = self.metadata.get(SCRIPT_INTERVAL)?.parse().ok()?;
+        Some(RotationScript::new(command, interval))
+    }
+
+    /// Sets the script that changes the Account's password on the service it's for
+    ///
+    /// # Arguments
+    ///
+    /// * `script` - The script, or `None` to only rotate the password by hand
+    pub fn set_rotation_script(&mut self, script: Option<RotationScript>) {
+        if script != self.rotation_script() {
+            self.touch();
+        }
+        match script {
+            Some(script) => {
+                self.metadata.set(SCRIPT_COMMAND, script.command());
+                self.metadata.set(SCRIPT_INTERVAL, script.interval());
+            }
+            None => {
+                for key in [
+                    SCRIPT_COMMAND,
+                    SCRIPT_INTERVAL,
+                    SCRIPT_LAST_RUN,
+                    SCRIPT_RESULT,
+                ] {
+                    self.metadata.remove(key);
+                }
+            }
+        }
+    }
+
+    /// Returns how the last run of the Account's rotation script went
+    ///
+    /// # Returns
+    ///
+    /// `None` if the script has never been run, otherwise when it ran and whether it worked
+    pub fn last_script_run(&self) -> Option<ScriptRun> {
+        let time = self.metadata.get(SCRIPT_LAST_RUN)?.parse().ok()?;
+        let result = match self.metadata.get(SCRIPT_RESULT)? {
+            "ok" => Ok(()),
+            error => Err(error.to_string()),
+        };
+        Some(ScriptRun { time, result })
+    }
+
+    /// Records how a run of the Account's rotation script went
+    ///
+    /// # Arguments
+    ///
+    /// * `run` - When the script ran and whether it worked
+    pub fn record_script_run(&mut self, run: &ScriptRun) {
+        self.metadata.set(SCRIPT_LAST_RUN, run.time);
+        match &run.result {
+            Ok(()) => self.metadata.set(SCRIPT_RESULT, "ok"),
+            Err(error) => self.metadata.set(SCRIPT_RESULT, error),
+        }
+    }
+
+    /// Returns the rules the Account's site enforces on passwords
+    ///
+    /// # Returns
+    ///
+    /// `None` if the Account has no policy, otherwise the policy
+    pub fn policy(&self) -> Option<Policy> {
+        Policy::from_rules(self.metadata.get(POLICY)?)
+    }
+
+    /// Sets the rules the Account's site enforces on passwords
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The policy, or `None` to remove it
+    pub fn set_policy(&mut self, policy: Option<Policy>) {
+        if policy.as_ref().map(Policy::to_rules).as_deref() != self.metadata.get(POLICY) {
+            self.touch();
+        }
+        match policy {
+            Some(policy) => self.metadata.set(POLICY, policy.to_rules()),
+            None => self.metadata.remove(POLICY),
+        }
+    }
+
+    /// Returns when the Account's password expires
+    ///
+    /// # Returns
+    ///
+    /// `None` if the password doesn't expire, otherwise the date or number of days it lasts
+    pub fn expiry(&self) -> Option<Expiry> {
+        if let Some(time) = self.metadata.get(EXPIRES_ON) {
+            return time.parse().ok().map(Expiry::On);
+        }
+        self.metadata
+            .get(EXPIRES_EVERY)?
+            .parse()
+            .ok()
+            .map(Expiry::Every)
+    }
+
+    /// Sets when the Account's password expires
+    ///
+    /// # Arguments
+    ///
+    /// * `expiry` - The date or number of days the password lasts, or `None` if it doesn't expire
+    pub fn set_expiry(&mut self, expiry: Option<Expiry>) {
+        if expiry != self.expiry() {
+            self.touch();
+        }
+        self.metadata.remove(EXPIRES_ON);
+        self.metadata.remove(EXPIRES_EVERY);
+        match expiry {
+            Some(Expiry::On(time)) => self.metadata.set(EXPIRES_ON, time),
+            Some(Expiry::Every(days)) => self.metadata.set(EXPIRES_EVERY, days),
+            None => (),
+        }
+    }
+
+    /// Returns when the Account's password expires, in seconds since the Unix epoch
+    pub fn expires_at(&self) -> Option<u64> {
+        self.expiry()?.expires_at(self)
+    }
+
+    /// Returns when the Account expires and is moved to the trash, for temporary credentials
+    ///
+    /// # Returns
+    ///
+    /// `None` if the Account isn't temporary, otherwise the time in seconds since the Unix epoch
+    pub fn deletes_on(&self) -> Option<u64> {
+        self.metadata.get(DELETES_ON)?.parse().ok()
+    }
+
+    /// Sets when the Account expires and is moved to the trash
+    ///
+    /// # Arguments
+    ///
+    /// * `deletes_on` - The time in seconds since the Unix epoch, or `None` to keep the Account
+    pub fn set_deletes_on(&mut self, deletes_on: Option<u64>) {
+        if deletes_on != self.deletes_on() {
+            self.touch();
+        }
+        match deletes_on {
+            Some(time) => self.metadata.set(DELETES_ON, time),
+            None => self.metadata.remove(DELETES_ON),
+        }
+    }
+
+    /// Returns when the Account was moved to the trash
+    ///
+    /// # Returns
+    ///
+    /// `None` if the Account isn't in the trash, otherwise the time in seconds since the Unix
+    /// epoch
+    pub fn trashed(&self) -> Option<u64> {
+        self.metadata.get(TRASHED)?.parse().ok()
+    }
+
+    /// Marks the Account as moved to the trash
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time, in seconds since the Unix epoch
+    pub fn move_to_trash(&mut self, now: u64) {
+        self.metadata.set(TRASHED, now);
+    }
+
+    /// Takes the Account back out of the trash. A temporary account whose date has passed stops
+    /// being temporary, so it isn't moved straight back
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time, in seconds since the Unix epoch
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::account::Account;
+    /// let mut account = Account::builder().build();
+    /// account.set_deletes_on(Some(100));
+    /// account.move_to_trash(100);
+    ///
+    /// account.restore_from_trash(200);
+    /// assert_eq!(account.trashed(), None);
+    /// assert_eq!(account.deletes_on(), None);
+    /// ```
+    pub fn restore_from_trash(&mut self, now: u64) {
+        self.metadata.remove(TRASHED);
+        if self
+            .deletes_on()
+            .is_some_and(|deletes_on| deletes_on <= now)
+        {
+            self.set_deletes_on(None);
+        }
+    }
+
+    /// Returns the Account's password, deriving it if it isn't stored
+    ///
+    /// # Arguments
+    ///
+    /// * `master` - The master password derived passwords come from
+    ///
+    /// # Returns
+    ///
+    /// Either the password, or why it couldn't be derived
+    pub fn resolve_password(&self, master: &str) -> Result<String, String> {
+        match self.derivation() {
+            Some(derivation) => derivation.derive(master.trim()),
+            None => Ok(self.password()),
+        }
+    }
+
     /// Encrypts the Account into a string with the stored encrypter
     ///
     /// # Arguments
@@ -93,6 +817,11 @@ impl Account {
     ///
     /// A string with newline breaks for each element
     pub fn encrypt(&self, encrypter: &MagicCrypt256) -> String {
+        let encrypted_metadata = match self.metadata.is_empty() {
+            true => "".to_string(),
+            false => encrypter.encrypt_to_base64(&self.metadata.to_line()),
+        };
+
         let encrypted_label = encrypter.encrypt_to_base64(&self.label);
 
         let encrypted_email = match &self.email {
@@ -108,8 +837,12 @@ impl Account {
         let encrypted_password = encrypter.encrypt_to_base64(&self.password);
 
         format!(
-            "{}\n{}\n{}\n{}",
-            encrypted_label, encrypted_username, encrypted_email, encrypted_password
+            "{}\n{}\n{}\n{}\n{}",
+            encrypted_label,
+            encrypted_username,
+            encrypted_email,
+            encrypted_password,
+            encrypted_metadata
         )
     }
 }
@@ -121,6 +854,8 @@ pub struct AccountBuilder {
     username: Option<String>,
     email: Option<String>,
     password: String,
+    metadata: String,
+    created: Option<u64>,
 }
 
 impl AccountBuilder {
@@ -133,6 +868,8 @@ impl AccountBuilder {
     /// * `username` - None
     /// * `email` - None
     /// * `password` - Empty String
+    /// * `metadata` - Empty String
+    /// * `created` - None
     pub fn new() -> Self {
         Self {
             decrypter: None,
@@ -140,6 +877,8 @@ impl AccountBuilder {
             username: None,
             email: None,
             password: String::new(),
+            metadata: String::new(),
+            created: None,
         }
     }
 
@@ -189,6 +928,18 @@ impl AccountBuilder {
                     .decrypt_base64_to_string(&self.label)
                     .expect("Label failed to desrypt");
             }
+
+            if !self.metadata.is_empty() {
+                self.metadata = decrypter
+                    .decrypt_base64_to_string(&self.metadata)
+                    .expect("Metadata failed to decrypt");
+            }
+        }
+
+        let mut metadata = Metadata::parse(&self.metadata);
+        if let Some(created) = self.created {
+            metadata.set(CREATED, created);
+            metadata.set(MODIFIED, created);
         }
 
         Account {
@@ -196,9 +947,30 @@ impl AccountBuilder {
             username: self.username.clone(),
             email: self.email.clone(),
             password: self.password.clone(),
+            metadata,
         }
     }
 
+    /// Records when the Account was created, for accounts that are new rather than loaded from
+    /// the vault
+    ///
+    /// # Arguments
+    ///
+    /// * `created` - When the Account was created, in seconds since the Unix epoch
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::account::AccountBuilder;
+    /// let account = AccountBuilder::new().created(60).build();
+    /// assert_eq!(account.created(), Some(60));
+    /// assert_eq!(account.modified(), Some(60));
+    /// ```
+    pub fn created(&mut self, created: u64) -> &mut Self {
+        self.created = Some(created);
+        self
+    }
+
     /// Changes the password of the Account
     ///
     /// # Arguments
@@ -271,6 +1043,24 @@ impl AccountBuilder {
         self
     }
 
+    /// Changes the metadata of the Account
+    ///
+    /// # Arguments
+    ///
+    /// * `metadata` - The metadata of the Account, in the single line format it's saved in
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::account::AccountBuilder;
+    /// let account = AccountBuilder::new().metadata("derive.counter=2").build();
+    /// assert_eq!(account.metadata().get("derive.counter"), Some("2"));
+    /// ```
+    pub fn metadata<T: ToString>(&mut self, metadata: T) -> &mut Self {
+        self.metadata = metadata.to_string();
+        self
+    }
+
     /// Whether the account should decrypt the given information.
     ///
     /// # Arguments
@@ -0,0 +1,189 @@
+//! When an account's password expires, either on a fixed date or a number of days after it last
+//! changed, like credentials that have to be changed every 90 days
+
+use std::fmt;
+
+use crate::time::{format_date, parse_date, DAY};
+
+use super::Account;
+
+/// Enum of the ways a password can expire
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expiry {
+    /// The password expires at the given time, in seconds since the Unix epoch
+    On(u64),
+    /// The password expires the given number of days after it last changed
+    Every(u64),
+}
+
+impl Expiry {
+    /// Parses an expiry as the user types it, either a date like `2024-02-29` or a number of days
+    /// like `90` or `90d`
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - What the user typed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::account::Expiry;
+    /// assert_eq!(Expiry::parse("90d"), Some(Expiry::Every(90)));
+    /// assert_eq!(Expiry::parse("1970-01-02"), Some(Expiry::On(86_400)));
+    /// assert_eq!(Expiry::parse("0"), None);
+    /// ```
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let days = text
+            .strip_suffix(" days")
+            .or_else(|| text.strip_suffix('d'))
+            .unwrap_or(text);
+
+        match days.parse() {
+            Ok(0) => None,
+            Ok(days) => Some(Expiry::Every(days)),
+            Err(_) => parse_date(text).map(Expiry::On),
+        }
+    }
+
+    /// Returns when an account's password expires
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The account the expiry is for
+    ///
+    /// # Returns
+    ///
+    /// `None` if the password expires some days after it changed but that time isn't known,
+    /// otherwise the time in seconds since the Unix epoch
+    pub fn expires_at(&self, account: &Account) -> Option<u64> {
+        match self {
+            Expiry::On(time) => Some(*time),
+            Expiry::Every(days) => {
+                // Accounts from before password changes were recorded count from when they were
+                // created, as later edits to other details don't change the password
+                let changed = account.password_changed().or(account.created())?;
+                Some(changed.saturating_add(days.saturating_mul(DAY)))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expiry {
+    /// Writes the expiry the same way it's typed, so it can be parsed again
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expiry::On(time) => write!(f, "{}", format_date(*time)),
+            Expiry::Every(days) => write!(f, "{} days", days),
+        }
+    }
+}
+
+/// Returns how long is left until a password expires, or how long ago it did, like `in 5 days` or
+/// `3 days ago`
+///
+/// # Arguments
+///
+/// * `expires` - When the password expires, in seconds since the Unix epoch
+/// * `now`     - The current time, in seconds since the Unix epoch
+///
+/// # Example
+///
+/// ```
+/// # use password_manager::account::describe_expiry;
+/// # use password_manager::time::DAY;
+/// assert_eq!(describe_expiry(3 * DAY, 0), "expires in 3 days");
+/// assert_eq!(describe_expiry(DAY, DAY + 5), "expired today");
+/// assert_eq!(describe_expiry(0, 2 * DAY), "expired 2 days ago");
+/// ```
+pub fn describe_expiry(expires: u64, now: u64) -> String {
+    let plural = |days: u64| match days {
+        1 => "1 day".to_string(),
+        days => format!("{} days", days),
+    };
+
+    match expires.checked_sub(now) {
+        Some(left) if left > 0 && left < DAY => "expires today".to_string(),
+        Some(left) if left > 0 => format!("expires in {}", plural(left.div_ceil(DAY))),
+        _ => match (now - expires) / DAY {
+            0 => "expired today".to_string(),
+            days => format!("expired {} ago", plural(days)),
+        },
+    }
+}
+
+/// Returns the accounts whose passwords have expired or expire soon, soonest first. Accounts in
+/// the trash are left out
+///
+/// # Arguments
+///
+/// * `accounts` - The accounts in the vault
+/// * `now`      - The current time, in seconds since the Unix epoch
+/// * `within`   - How many days ahead to look
+///
+/// # Returns
+///
+/// The positions of the accounts paired with when their passwords expire
+pub fn expiring(accounts: &[Account], now: u64, within: u64) -> Vec<(usize, u64)> {
+    let horizon = now.saturating_add(within.saturating_mul(DAY));
+    let mut expiring: Vec<(usize, u64)> = accounts
+        .iter()
+        .enumerate()
+        .filter(|(_, account)| account.trashed().is_none())
+        .filter_map(|(index, account)| Some((index, account.expires_at()?)))
+        .filter(|(_, expires)| *expires <= horizon)
+        .collect();
+
+    expiring.sort_by_key(|(_, expires)| *expires);
+    expiring
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expiring, Expiry};
+    use crate::account::Account;
+    use crate::time::DAY;
+
+    #[test]
+    /// Tests that only accounts expiring within the window are listed, soonest first
+    fn expiring_works() {
+        let mut accounts = vec![
+            Account::builder().label("later").build(),
+            Account::builder().label("never").build(),
+            Account::builder().label("expired").build(),
+            Account::builder().label("soon").build(),
+        ];
+        accounts[0].set_expiry(Some(Expiry::On(40 * DAY)));
+        accounts[2].set_expiry(Some(Expiry::On(5 * DAY)));
+        accounts[3].set_expiry(Some(Expiry::On(20 * DAY)));
+
+        assert_eq!(
+            expiring(&accounts, 10 * DAY, 14),
+            vec![(2, 5 * DAY), (3, 20 * DAY)]
+        );
+        assert_eq!(expiring(&accounts, 0, 0), vec![]);
+    }
+
+    #[test]
+    /// Tests that an interval counts from when the password last changed
+    fn every_counts_from_password_change() {
+        let mut account = Account::builder().password("old").build();
+        account.set_expiry(Some(Expiry::Every(90)));
+        account.set_password("new".to_string());
+
+        let changed = account.password_changed().unwrap();
+        assert_eq!(account.expires_at(), Some(changed + 90 * DAY));
+    }
+
+    #[test]
+    /// Tests that an interval on an account with no recorded password change counts from when it
+    /// was created, so editing other details doesn't push it back
+    fn every_falls_back_to_creation() {
+        let mut account = Account::builder().password("old").created(DAY).build();
+        account.set_expiry(Some(Expiry::Every(90)));
+        account.set_username(Some("me".to_string()));
+
+        assert!(account.modified().unwrap() > DAY);
+        assert_eq!(account.expires_at(), Some(91 * DAY));
+    }
+}
@@ -0,0 +1,138 @@
+//! Extra details stored alongside an account, as `key=value` pairs
+//!
+//! Metadata is saved as a single line, so entries are separated by `;` and any `\`, `;` or newline
+//! in a value is escaped with a backslash. Keys never contain `=`, so values can. Keys that aren't
+//! understood are kept as they are, so vaults written by newer versions aren't damaged when saved
+//! by older ones
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// A struct that stores an account's metadata in the order it was set
+pub struct Metadata {
+    entries: Vec<(String, String)>,
+}
+
+impl Metadata {
+    /// Parses metadata from the single line format it's saved in
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The saved metadata
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::account::Metadata;
+    /// let mut metadata = Metadata::default();
+    /// metadata.set("note", "a;b=c\\d");
+    ///
+    /// let parsed = Metadata::parse(&metadata.to_line());
+    /// assert_eq!(parsed.get("note"), Some("a;b=c\\d"));
+    /// ```
+    pub fn parse(line: &str) -> Self {
+        let mut entries = vec![];
+        let mut entry = String::new();
+        let mut chars = line.chars();
+
+        // Escaped characters are unescaped as they're read, so entries are only split on
+        // separators that weren't escaped
+        let mut push = |entry: &mut String| {
+            if let Some((key, value)) = entry.split_once('=') {
+                entries.push((key.to_string(), value.to_string()));
+            }
+            entry.clear();
+        };
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some('n') => entry.push('\n'),
+                    Some(c) => entry.push(c),
+                    None => (),
+                },
+                ';' => push(&mut entry),
+                c => entry.push(c),
+            }
+        }
+        push(&mut entry);
+
+        Self { entries }
+    }
+
+    /// Converts the metadata into the single line format it's saved in
+    pub fn to_line(&self) -> String {
+        self.entries
+            .iter()
+            .map(|(key, value)| {
+                let value = value
+                    .replace('\\', "\\\\")
+                    .replace(';', "\\;")
+                    .replace('\n', "\\n");
+                format!("{}={}", key, value)
+            })
+            .collect::<Vec<String>>()
+            .join(";")
+    }
+
+    /// Returns whether there is no metadata
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the value stored under the key
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to look up
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(x, _)| x == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Stores a value under the key, replacing any value already there
+    ///
+    /// # Arguments
+    ///
+    /// * `key`   - The key to store the value under. Must not contain `=`
+    /// * `value` - The value to store
+    pub fn set<T: ToString>(&mut self, key: &str, value: T) {
+        match self.entries.iter_mut().find(|(x, _)| x == key) {
+            Some(entry) => entry.1 = value.to_string(),
+            None => self.entries.push((key.to_string(), value.to_string())),
+        }
+    }
+
+    /// Removes the value stored under the key
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to remove
+    pub fn remove(&mut self, key: &str) {
+        self.entries.retain(|(x, _)| x != key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Metadata;
+
+    #[test]
+    /// Tests that values survive being saved, including ones that need escaping
+    fn metadata_round_trips() {
+        let mut metadata = Metadata::default();
+        metadata.set("plain", "value");
+        metadata.set("tricky", "semi;colon\\back=slash\nnewline");
+        metadata.set("plain", "replaced");
+
+        let line = metadata.to_line();
+        assert!(!line.contains('\n'));
+
+        let parsed = Metadata::parse(&line);
+        assert_eq!(parsed, metadata);
+        assert_eq!(parsed.get("plain"), Some("replaced"));
+        assert_eq!(parsed.get("missing"), None);
+
+        assert!(Metadata::parse("").is_empty());
+    }
+}
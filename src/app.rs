@@ -1,43 +1,161 @@
 //! This module contains the entry point for the CLI application
+mod audit;
+mod breaches;
 mod change_password;
+mod derive;
+mod generate;
 mod help;
+mod history;
 mod home;
+mod lookup;
 mod notification;
+mod rotate;
 mod search;
+mod trash;
 mod view;
 
-use crate::account::Account;
+use crate::account::{describe_expiry, expiring, Account};
+use crate::audit::accounts_using;
+use crate::clipboard::Clipboard;
+use crate::config::Config;
 use colored::Colorize;
 use crossterm::{
     cursor,
     terminal::{self, disable_raw_mode, enable_raw_mode},
 };
 
+use audit::{audit, AuditAction};
+use breaches::show_breach_report;
 use change_password::{change_password, PasswordResult};
 use home::{home, PageOption};
+use lookup::{prompt_password, show_accounts_using, LookupAction};
 use notification::show_notification;
-use search::{search, SearchAction};
-use view::view;
+use rotate::rotate_due;
+use search::{search, SearchAction, SearchIndex};
+use trash::{show_trash, TrashAction};
+use view::{view, ViewAction};
 
-use crate::serialisation::{deserialise, read_password_file, DeserialisationResult};
-use crate::terminal_drawing::{box_label, textfield};
+use crate::serialisation::{deserialise, read_password_file, serialise, DeserialisationResult};
+use crate::strength::estimate;
+use crate::terminal_drawing::{box_label, textfield, textfield_with_hint};
+use crate::time;
+use crate::trash::{purge_expired, split_trash, trash_expired};
 
 /// Prompts the user to login
 ///
+/// # Arguments
+///
+/// * `show_strength` - Whether to show how strong the password is as it's typed, for when a new
+///   vault is being created
+///
 /// # Returns
 ///
 /// Either the password the user entered, or an io error
-pub fn login() -> crossterm::Result<String> {
+pub fn login(show_strength: bool) -> crossterm::Result<String> {
     enable_raw_mode()?;
     let prompt = format!("{} ", box_label("Enter Password"));
-    let password = textfield(prompt, 17, "".to_string(), true)?;
+    let password = match show_strength {
+        true => textfield_with_hint(prompt, 17, "".to_string(), true, strength_hint)?,
+        false => textfield(prompt, 17, "".to_string(), true)?,
+    };
     disable_raw_mode()?;
     Ok(password.unwrap_or(String::new()))
 }
 
-/// Setup function, that reads the file, confirms the password, and returns the password submitted,
-/// magic_crypt codec, and the vector of accounts. If the user has not got a password file, then
-/// the program suggests creating a new one.
+/// Returns the strength meter drawn next to a password as it's typed, along with what makes it
+/// weak if it is
+///
+/// # Arguments
+///
+/// * `password` - The password being typed
+fn strength_hint(password: &str) -> String {
+    if password.is_empty() {
+        return String::new();
+    }
+
+    let estimate = estimate(password);
+    match estimate.warning() {
+        Some(warning) => format!("{}  {}", estimate.meter(), warning),
+        None => estimate.meter(),
+    }
+}
+
+/// Setup function, that unlocks the vault, purges accounts that have been in the trash too long,
+/// moves temporary accounts whose time is up to the trash, then reminds the user of any passwords
+/// that have expired or expire soon. Anything listed is left on screen until a key is pressed, so
+/// it's seen before the app opens
+///
+/// # Arguments
+///
+/// * `password_file` - The path of the password file
+/// * `config`        - The user's configuration, with how far ahead to look for expiring passwords
+///
+/// # Returns
+///
+/// `None` if the user enters the wrong password or chooses to not create a password file.
+/// Otherwise a tuple with a vectors of Accounts, including the trash, and the entered password
+pub fn setup(password_file: &str, config: &Config) -> Option<(Vec<Account>, String)> {
+    let (mut accounts, password) = unlock(password_file)?;
+    let now = time::now();
+
+    purge_expired(&mut accounts, config.trash_retention_days(), now);
+    let trashed = trash_expired(&mut accounts, now);
+    if !trashed.is_empty() {
+        println!(
+            "{} These temporary accounts have expired and were moved to the trash:",
+            " NOTICE ".black().on_yellow()
+        );
+        for index in &trashed {
+            println!("  {}", accounts[*index].label());
+        }
+    }
+    let reminded = remind_expiring(&accounts, config, now);
+
+    if !trashed.is_empty() || reminded {
+        println!("Press any key to continue");
+        enable_raw_mode().unwrap();
+        crate::terminal_drawing::get_confirmation().unwrap();
+        disable_raw_mode().unwrap();
+    }
+
+    Some((accounts, password))
+}
+
+/// Lists the accounts whose passwords have expired or expire within the configured number of days
+///
+/// # Arguments
+///
+/// * `accounts` - The accounts in the vault
+/// * `config`   - The user's configuration, with how far ahead to look
+/// * `now`      - The current time, in seconds since the Unix epoch
+///
+/// # Returns
+///
+/// Whether any accounts were listed
+fn remind_expiring(accounts: &[Account], config: &Config, now: u64) -> bool {
+    let expiring = expiring(accounts, now, config.expiry_warning_days());
+    if expiring.is_empty() {
+        return false;
+    }
+
+    println!(
+        "{} These passwords have expired or expire within {} days:",
+        " REMINDER ".black().on_yellow(),
+        config.expiry_warning_days()
+    );
+    for (index, expires) in expiring {
+        println!(
+            "  {}  {}",
+            accounts[index].label(),
+            describe_expiry(expires, now)
+        );
+    }
+    true
+}
+
+/// Reads the file, confirms the password, and returns the password submitted and the vector of
+/// accounts. If the user has not got a password file, then the program suggests creating a new
+/// one.
 ///
 /// # Arguments
 ///
@@ -46,11 +164,11 @@ pub fn login() -> crossterm::Result<String> {
 /// # Returns
 ///
 /// `None` if the user enters the wrong password or chooses to not create a password file.
-/// Otherwise a tuple with a vectors of Accounts, the entered password, then the codec used.
-pub fn setup(password_file: &str) -> Option<(Vec<Account>, String)> {
+/// Otherwise a tuple with a vectors of Accounts and the entered password
+pub fn unlock(password_file: &str) -> Option<(Vec<Account>, String)> {
     match read_password_file(password_file) {
         Ok(password_file) => {
-            let password = login().unwrap();
+            let password = login(false).unwrap();
 
             match deserialise(password_file, password.trim()) {
                 DeserialisationResult::WrongPassword => {
@@ -73,7 +191,7 @@ pub fn setup(password_file: &str) -> Option<(Vec<Account>, String)> {
             disable_raw_mode().unwrap();
 
             if confirmation {
-                let password = login().unwrap();
+                let password = login(true).unwrap();
                 return Some((vec![], password));
             }
 
@@ -86,52 +204,179 @@ pub fn setup(password_file: &str) -> Option<(Vec<Account>, String)> {
 ///
 /// # Arguments
 ///
-/// * `accounts` - The accounts to run the application with
-/// * `password` - The password to the vault
+/// * `accounts`      - The accounts to run the application with
+/// * `password`      - The password to the vault
+/// * `password_file` - The path of the password file, which is saved to straight away after
+///   changes that can't wait until the app closes
+/// * `config`        - The user's configuration
+/// * `clipboard`     - The clipboard to yank fields into, which is left serving the last yanked
+///   value so the vault can be saved before waiting for it with `wait_for_clipboard`
 ///
 /// # Returns
 ///
 /// A tuple with a Vec of accounts and the vaults password
-pub fn run(accounts: Vec<Account>, password: String) -> crossterm::Result<(Vec<Account>, String)> {
-    let mut accounts = accounts;
+pub fn run(
+    accounts: Vec<Account>,
+    password: String,
+    password_file: &str,
+    config: Config,
+    clipboard: &mut Clipboard,
+) -> crossterm::Result<(Vec<Account>, String)> {
+    // The trash is kept apart while the app runs, then saved with the vault's accounts
+    let (mut accounts, mut trash) = split_trash(accounts);
     let mut password = password;
+    let mut config = config;
+    let mut search_index = SearchIndex::new(&accounts);
 
     enable_raw_mode()?;
 
-    loop {
-        match home()? {
+    'app: loop {
+        let vault = Vault {
+            file: password_file,
+            password: &password,
+        };
+
+        match home(&accounts, clipboard)? {
             PageOption::Search => loop {
-                let search_result = search(&accounts)?;
+                let search_result =
+                    search(&mut accounts, &search_index, &password, &config, clipboard)?;
                 match search_result {
-                    SearchAction::ViewAccount(account_label) => {
-                        let index = accounts
-                            .iter()
-                            .position(|x| x.label() == account_label)
-                            .expect("Safe to unwrap as we know the label exists");
-                        match view(accounts[index].clone())? {
-                            Some(account) => {
-                                accounts[index] = account;
-                            }
-                            None => {
-                                accounts.remove(index);
-                            }
-                        }
-                    }
+                    SearchAction::ViewAccount(index) => view_account_and_sharing(
+                        index,
+                        &mut accounts,
+                        &mut trash,
+                        &mut search_index,
+                        &vault,
+                        &mut config,
+                        clipboard,
+                    )?,
                     SearchAction::NewAccount(new_account_label) => {
-                        let new_account =
-                            view::view(Account::builder().label(new_account_label).build())?;
+                        let action = view(
+                            Account::builder()
+                                .label(new_account_label)
+                                .created(time::now())
+                                .build(),
+                            &password,
+                            &mut config,
+                            clipboard,
+                        )?;
 
-                        if let Some(new_account) = new_account {
-                            if !account_with_label(&accounts, &new_account.label()) {
-                                accounts.push(new_account);
+                        let (mut new_account, shared, rotated) = match action {
+                            ViewAction::Save(account) => (account, None, false),
+                            ViewAction::FindSharing(account, shared) => {
+                                (account, Some(shared), false)
                             }
+                            ViewAction::Rotated(account) => (account, None, true),
+                            ViewAction::Delete => continue,
+                        };
+                        // The account is never dropped, as a rotation script may have already
+                        // changed its password on the service
+                        let label = new_account.label();
+                        if account_with_label(&accounts, &label) {
+                            let unique = unique_label(&accounts, &label);
+                            show_notification(format!(
+                                "There's already an account called {}, so this one was saved as {}",
+                                label, unique
+                            ))?;
+                            new_account.set_label(unique);
+                        }
+                        search_index.insert(&new_account);
+                        accounts.push(new_account);
+
+                        if rotated {
+                            vault.save(&accounts, &trash)?;
+                            view_account_and_sharing(
+                                accounts.len() - 1,
+                                &mut accounts,
+                                &mut trash,
+                                &mut search_index,
+                                &vault,
+                                &mut config,
+                                clipboard,
+                            )?;
+                        }
+                        if let Some(shared) = shared {
+                            list_accounts_using(
+                                shared,
+                                &mut accounts,
+                                &mut trash,
+                                &mut search_index,
+                                &vault,
+                                &mut config,
+                                clipboard,
+                            )?;
                         }
                     }
                     SearchAction::Exit => break,
+                    SearchAction::Quit => break 'app,
                 };
             },
+            PageOption::Audit => {
+                while let AuditAction::ViewAccount(index) =
+                    audit(&accounts, &password, &config, clipboard)?
+                {
+                    view_account_and_sharing(
+                        index,
+                        &mut accounts,
+                        &mut trash,
+                        &mut search_index,
+                        &vault,
+                        &mut config,
+                        clipboard,
+                    )?;
+                }
+            }
+            PageOption::Lookup => {
+                if let Some(shared) = prompt_password()? {
+                    list_accounts_using(
+                        shared,
+                        &mut accounts,
+                        &mut trash,
+                        &mut search_index,
+                        &vault,
+                        &mut config,
+                        clipboard,
+                    )?;
+                }
+            }
+            PageOption::Breaches => show_breach_report(&accounts, &password, &config, clipboard)?,
+            PageOption::RotateDue => rotate_due(&mut accounts, &trash, &vault, clipboard)?,
+            PageOption::Trash => loop {
+                match show_trash(&trash, config.trash_retention_days(), clipboard)? {
+                    TrashAction::Restore(position) => {
+                        if account_with_label(&accounts, &trash[position].label()) {
+                            show_notification(format!(
+                                "There's already an account called {}, rename it to restore \
+                                 this one",
+                                trash[position].label()
+                            ))?;
+                            continue;
+                        }
+                        let mut account = trash.remove(position);
+                        account.restore_from_trash(time::now());
+                        search_index.insert(&account);
+                        accounts.push(account);
+                    }
+                    TrashAction::Purge(position) => {
+                        trash.remove(position);
+                    }
+                    TrashAction::Exit => break,
+                }
+            },
             PageOption::ChangePassword => match change_password(password.trim())? {
-                PasswordResult::NewPassword(new_password) => password = new_password,
+                PasswordResult::NewPassword(new_password) => {
+                    // Derived passwords in the trash would be lost too if they weren't stored
+                    let stored = store_derived_passwords(&mut accounts, &password)
+                        + store_derived_passwords(&mut trash, &password);
+                    password = new_password;
+                    if stored > 0 {
+                        show_notification(format!(
+                            "{} derived passwords came from the old password, so they're \
+                             now stored",
+                            stored
+                        ))?;
+                    }
+                }
                 PasswordResult::Error(error) => show_notification(error)?,
                 PasswordResult::None => (),
             },
@@ -149,9 +394,218 @@ pub fn run(accounts: Vec<Account>, password: String) -> crossterm::Result<(Vec<A
 
     disable_raw_mode()?;
 
+    accounts.extend(trash);
     Ok((accounts, password))
 }
 
+/// Keeps serving the last yanked value until the clipboard clears itself or the user presses a
+/// key. This is only called once the vault has been saved, so nothing is lost if the terminal is
+/// closed while waiting
+///
+/// # Arguments
+///
+/// * `clipboard` - The clipboard the password manager yanked into
+pub fn wait_for_clipboard(clipboard: &mut Clipboard) -> crossterm::Result<()> {
+    enable_raw_mode()?;
+    clipboard.wait_until_cleared()?;
+
+    crossterm::execute!(
+        std::io::stdout(),
+        cursor::MoveTo(0, 0),
+        terminal::Clear(terminal::ClearType::All),
+        cursor::Show
+    )?;
+
+    disable_raw_mode()
+}
+
+/// Views an account in the vault, saving any changes made to it or moving it to the trash if it's
+/// deleted
+///
+/// # Arguments
+///
+/// * `index`        - The position of the account in the vault
+/// * `accounts`     - The accounts in the vault
+/// * `trash`        - The accounts in the trash, which deleted accounts are moved to
+/// * `search_index` - The search index over the vault's accounts, kept up to date with changes
+/// * `vault`        - Where the vault is saved and the password to it
+/// * `config`       - The user's configuration
+/// * `clipboard`    - The clipboard to yank fields into
+///
+/// # Returns
+///
+/// The account's password if the user asked to list every account using it, or an IO error
+fn view_account(
+    index: usize,
+    accounts: &mut Vec<Account>,
+    trash: &mut Vec<Account>,
+    search_index: &mut SearchIndex,
+    vault: &Vault,
+    config: &mut Config,
+    clipboard: &mut Clipboard,
+) -> crossterm::Result<Option<String>> {
+    let mut account = accounts[index].clone();
+    let (account, shared) = loop {
+        match view(account, vault.password, config, clipboard)? {
+            ViewAction::Save(account) => break (account, None),
+            ViewAction::FindSharing(account, shared) => break (account, Some(shared)),
+            // The script has already changed the password on the service, so the vault is saved
+            // before the account is viewed again
+            ViewAction::Rotated(rotated) => {
+                search_index.update(index, &rotated);
+                accounts[index] = rotated.clone();
+                vault.save(accounts, trash)?;
+                account = rotated;
+            }
+            ViewAction::Delete => {
+                search_index.remove(index);
+                let mut account = accounts.remove(index);
+                account.move_to_trash(time::now());
+                trash.push(account);
+                return Ok(None);
+            }
+        }
+    };
+
+    search_index.update(index, &account);
+    accounts[index] = account;
+    Ok(shared)
+}
+
+/// Views an account in the vault, then lists every account using its password if the user asks
+///
+/// # Arguments
+///
+/// * `index`        - The position of the account in the vault
+/// * `accounts`     - The accounts in the vault
+/// * `trash`        - The accounts in the trash, which deleted accounts are moved to
+/// * `search_index` - The search index over the vault's accounts, kept up to date with changes
+/// * `vault`        - Where the vault is saved and the password to it
+/// * `config`       - The user's configuration
+/// * `clipboard`    - The clipboard to yank fields into
+fn view_account_and_sharing(
+    index: usize,
+    accounts: &mut Vec<Account>,
+    trash: &mut Vec<Account>,
+    search_index: &mut SearchIndex,
+    vault: &Vault,
+    config: &mut Config,
+    clipboard: &mut Clipboard,
+) -> crossterm::Result<()> {
+    if let Some(shared) = view_account(
+        index,
+        accounts,
+        trash,
+        search_index,
+        vault,
+        config,
+        clipboard,
+    )? {
+        list_accounts_using(
+            shared,
+            accounts,
+            trash,
+            search_index,
+            vault,
+            config,
+            clipboard,
+        )?;
+    }
+    Ok(())
+}
+
+/// Lists every account using a password, letting the user view each one to change it. The list
+/// is looked up again after each account is viewed, so changed accounts drop off it
+///
+/// # Arguments
+///
+/// * `shared`       - The password to look for
+/// * `accounts`     - The accounts in the vault
+/// * `trash`        - The accounts in the trash, which deleted accounts are moved to
+/// * `search_index` - The search index over the vault's accounts, kept up to date with changes
+/// * `vault`        - Where the vault is saved and the password to it
+/// * `config`       - The user's configuration
+/// * `clipboard`    - The clipboard to yank fields into
+fn list_accounts_using(
+    shared: String,
+    accounts: &mut Vec<Account>,
+    trash: &mut Vec<Account>,
+    search_index: &mut SearchIndex,
+    vault: &Vault,
+    config: &mut Config,
+    clipboard: &mut Clipboard,
+) -> crossterm::Result<()> {
+    let mut shared = shared;
+
+    loop {
+        let using = accounts_using(accounts, &shared, vault.password);
+        match show_accounts_using(accounts, &using, clipboard)? {
+            LookupAction::ViewAccount(index) => {
+                // Asking for the accounts sharing another password switches the list over to it
+                if let Some(other) = view_account(
+                    index,
+                    accounts,
+                    trash,
+                    search_index,
+                    vault,
+                    config,
+                    clipboard,
+                )? {
+                    shared = other;
+                }
+            }
+            LookupAction::Exit => return Ok(()),
+        }
+    }
+}
+
+/// Where the vault is saved and the password it's encrypted with, so changes that can't wait until
+/// the app closes can be saved straight away
+struct Vault<'a> {
+    /// The path of the password file
+    file: &'a str,
+    /// The password to the vault, which derived passwords also come from
+    password: &'a str,
+}
+
+impl Vault<'_> {
+    /// Saves the accounts and the trash to the password file, showing a notification if it
+    /// can't be written
+    ///
+    /// # Arguments
+    ///
+    /// * `accounts` - The accounts in the vault
+    /// * `trash`    - The accounts in the trash
+    fn save(&self, accounts: &[Account], trash: &[Account]) -> crossterm::Result<()> {
+        let accounts = accounts.iter().chain(trash).cloned().collect();
+        if let Err(error) = serialise(accounts, self.file, self.password.trim()) {
+            show_notification(format!("Couldn't save the vault: {}", error))?;
+        }
+        Ok(())
+    }
+}
+
+/// Stores the passwords of accounts that derive them, so they keep working once the master
+/// password they were derived from changes
+///
+/// # Arguments
+///
+/// * `accounts` - The accounts to check
+/// * `master`   - The master password the passwords were derived from
+///
+/// # Returns
+///
+/// How many passwords were stored
+fn store_derived_passwords(accounts: &mut [Account], master: &str) -> usize {
+    let mut stored = 0;
+    for account in accounts.iter_mut() {
+        if let Ok(true) = account.store_derived_password(master) {
+            stored += 1;
+        }
+    }
+    stored
+}
+
 /// Returns whether the given accounts slice has an account with the given label
 ///
 /// # Arguments
@@ -167,9 +621,22 @@ fn account_with_label(accounts: &[Account], new_label: &str) -> bool {
     false
 }
 
+/// Returns the label with the lowest number after it that no account has, such as `GitHub (2)`
+///
+/// # Arguments
+///
+/// * `accounts`  - The accounts in the vault
+/// * `new_label` - The label that's already taken
+fn unique_label(accounts: &[Account], new_label: &str) -> String {
+    (2..)
+        .map(|number| format!("{} ({})", new_label, number))
+        .find(|label| !account_with_label(accounts, label))
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
-    use super::account_with_label;
+    use super::{account_with_label, unique_label};
     use crate::account::Account;
     #[test]
     /// This function tests that the account_with_label() function works as expected
@@ -184,4 +651,16 @@ mod tests {
         assert!(account_with_label(&accounts, "Test 2"));
         assert!(!account_with_label(&accounts, "Test 4"));
     }
+
+    #[test]
+    /// Tests that taken labels get the lowest free number after them
+    fn unique_label_works() {
+        let accounts = vec![
+            Account::builder().label("Test").build(),
+            Account::builder().label("Test (2)").build(),
+        ];
+
+        assert_eq!(unique_label(&accounts, "Test"), "Test (3)");
+        assert_eq!(unique_label(&accounts, "Other"), "Other (2)");
+    }
 }
@@ -0,0 +1,154 @@
+//! This module contains the audit page, which lists the accounts with passwords or details that
+//! need attention so they can be opened and fixed
+
+use colored::Colorize;
+use crossterm::{
+    cursor,
+    event::{Event, KeyCode},
+    execute,
+    terminal::{Clear, ClearType},
+    Result,
+};
+use std::io::stdout;
+
+use crate::account::Account;
+use crate::audit::{Audit, Report};
+use crate::breach::BreachList;
+use crate::clipboard::Clipboard;
+use crate::config::Config;
+use crate::terminal_drawing::{box_label, println, textfield};
+use crate::time;
+
+use super::notification::show_notification;
+
+/// Where reports are saved unless the user picks somewhere else. The extension picks the format
+const DEFAULT_REPORT_PATH: &str = "audit-report.html";
+
+/// Enum of what the user chose on the audit page
+pub enum AuditAction {
+    /// View the account at the given position in the vault
+    ViewAccount(usize),
+    /// Go back to the home page
+    Exit,
+}
+
+/// Entry point for the audit page. Audits the vault then lets the user pick an account to fix
+///
+/// # Arguments
+///
+/// * `accounts`  - The accounts in the vault
+/// * `master`    - The master password derived passwords come from
+/// * `config`    - The user's configuration, with the audit's thresholds
+/// * `clipboard` - The clipboard, to show the status of
+pub fn audit(
+    accounts: &[Account],
+    master: &str,
+    config: &Config,
+    clipboard: &mut Clipboard,
+) -> Result<AuditAction> {
+    execute!(
+        stdout(),
+        cursor::MoveTo(0, 0),
+        Clear(ClearType::FromCursorDown),
+        cursor::Hide
+    )?;
+    println(box_label("Audit"))?;
+    println(format!("Auditing {} accounts...", accounts.len()))?;
+
+    let mut audit = Audit::run(accounts, master, &config.audit_options(), time::now());
+    if let Some(breach_file) = config.breach_file() {
+        let checked = BreachList::open(breach_file)
+            .and_then(|list| audit.check_breaches(accounts, master, &list));
+        if let Err(error) = checked {
+            show_notification(error)?;
+        }
+    }
+    let findings: Vec<usize> = audit
+        .sections()
+        .iter()
+        .flat_map(|(_, findings)| findings.iter().map(|finding| finding.index))
+        .collect();
+    let mut selected = 0;
+
+    loop {
+        draw_audit(accounts, &audit, selected)?;
+        if let Event::Key(key) = clipboard.read_event()? {
+            match key.code {
+                KeyCode::Char('j') | KeyCode::Down if selected + 1 < findings.len() => {
+                    selected += 1
+                }
+                KeyCode::Char('k') | KeyCode::Up => selected = selected.saturating_sub(1),
+                KeyCode::Enter => {
+                    if let Some(index) = findings.get(selected) {
+                        return Ok(AuditAction::ViewAccount(*index));
+                    }
+                }
+                KeyCode::Char('x') => export(accounts, &audit)?,
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(AuditAction::Exit),
+                _ => (),
+            }
+        }
+    }
+}
+
+/// Asks the user where to save a report of the audit, then saves it
+///
+/// # Arguments
+///
+/// * `accounts` - The accounts that were audited
+/// * `audit`    - What the audit found
+fn export(accounts: &[Account], audit: &Audit) -> Result<()> {
+    execute!(stdout(), cursor::MoveTo(0, 0))?;
+    let path = textfield(
+        format!("{} ", box_label("Save report to")),
+        16,
+        DEFAULT_REPORT_PATH.to_string(),
+        false,
+    )?;
+
+    if let Some(path) = path.map(|x| x.trim().to_string()) {
+        match Report::new(audit, accounts, time::now()).save(&path) {
+            Ok(()) => show_notification(format!("Saved the audit report to {}", path))?,
+            Err(error) => show_notification(error)?,
+        }
+    }
+    Ok(())
+}
+
+/// Draws the audit page to the terminal
+///
+/// # Arguments
+///
+/// * `accounts` - The accounts in the vault
+/// * `audit`    - What the audit found
+/// * `selected` - The position of the selected finding, counting across every section
+fn draw_audit(accounts: &[Account], audit: &Audit, selected: usize) -> Result<()> {
+    execute!(
+        stdout(),
+        cursor::MoveTo(0, 1),
+        Clear(ClearType::FromCursorDown)
+    )?;
+
+    if audit.is_empty() {
+        println("No problems found")?;
+    }
+
+    let mut position = 0;
+    for (issue, findings) in audit.sections() {
+        println(format!("{} ({})", issue.title().bold(), findings.len()))?;
+        for finding in findings {
+            println(format!(
+                "{} {}  {}",
+                if position == selected { '>' } else { ' ' },
+                accounts[finding.index].label(),
+                finding.detail.dimmed()
+            ))?;
+            position += 1;
+        }
+    }
+
+    println("")?;
+    println("j/k move  ENTER view account  x export report  ESC back")?;
+
+    Ok(())
+}
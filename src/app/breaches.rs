@@ -0,0 +1,80 @@
+//! This module contains the breach report, which lists the accounts whose passwords appear in the
+//! configured list of breached passwords
+
+use crossterm::{
+    cursor,
+    event::Event,
+    execute,
+    terminal::{Clear, ClearType},
+    Result,
+};
+use std::io::stdout;
+
+use crate::account::Account;
+use crate::breach::BreachList;
+use crate::clipboard::Clipboard;
+use crate::config::Config;
+use crate::terminal_drawing::{box_label, println};
+
+use super::notification::show_notification;
+
+/// Checks every account against the breach list and shows which ones were found, until the user
+/// presses a key
+///
+/// # Arguments
+///
+/// * `accounts`  - The accounts to check
+/// * `master`    - The master password derived passwords come from
+/// * `config`    - The user's configuration, with the path of the breach list
+/// * `clipboard` - The clipboard, to show the status of
+pub fn show_breach_report(
+    accounts: &[Account],
+    master: &str,
+    config: &Config,
+    clipboard: &mut Clipboard,
+) -> Result<()> {
+    let Some(breach_file) = config.breach_file() else {
+        return show_notification(
+            "Set breach_file in the config to a Pwned Passwords hash file to check for breaches",
+        );
+    };
+
+    execute!(
+        stdout(),
+        cursor::MoveTo(0, 0),
+        Clear(ClearType::FromCursorDown),
+        cursor::Hide
+    )?;
+    println(box_label("Breach Report"))?;
+    println(format!("Checking {} accounts...", accounts.len()))?;
+
+    let breaches = match BreachList::open(breach_file)
+        .and_then(|list| list.check_accounts(accounts, master.trim()))
+    {
+        Ok(breaches) => breaches,
+        Err(error) => return show_notification(error),
+    };
+
+    execute!(
+        stdout(),
+        cursor::MoveTo(0, 1),
+        Clear(ClearType::FromCursorDown)
+    )?;
+    match breaches.is_empty() {
+        true => println("None of your passwords have been seen in breaches")?,
+        false => {
+            for breach in &breaches {
+                println(format!(
+                    " {}  seen {} times",
+                    accounts[breach.index].label(),
+                    breach.count
+                ))?;
+            }
+        }
+    }
+    println("")?;
+    println("Press any key to go back")?;
+
+    while !matches!(clipboard.read_event()?, Event::Key(_)) {}
+    Ok(())
+}
@@ -1,6 +1,7 @@
 //! This module contains the change password screen logic
 
-use crate::terminal_drawing::{box_label, textfield};
+use super::strength_hint;
+use crate::terminal_drawing::{box_label, textfield, textfield_with_hint};
 use crossterm::{
     cursor, execute,
     terminal::{Clear, ClearType},
@@ -35,11 +36,12 @@ pub fn change_password(old_password: &str) -> crossterm::Result<PasswordResult>
         return Ok(PasswordResult::Error("Incorrect password!".to_string()));
     }
 
-    let new_password = match textfield(
+    let new_password = match textfield_with_hint(
         format!("{} ", box_label("Enter New Password")),
         21,
         "".to_string(),
         true,
+        strength_hint,
     )? {
         Some(new_password) => new_password.trim().to_string(),
         None => return Ok(PasswordResult::None),
@@ -0,0 +1,245 @@
+//! This module contains the derivation dialog, where the user picks what an account's password is
+//! derived from instead of storing it
+
+use crossterm::{
+    cursor,
+    event::{Event, KeyCode},
+    execute,
+    terminal::{Clear, ClearType},
+    Result,
+};
+use std::io::stdout;
+
+use crate::clipboard::Clipboard;
+use crate::generator::DerivationOptions;
+use crate::policy::Policy;
+use crate::terminal_drawing::{box_label, print, println, textfield};
+
+use DerivationField::*;
+
+/// The line the preview of the derived password is drawn on
+const PREVIEW_LINE: u16 = 9;
+/// The line prompts are drawn on
+const PROMPT_LINE: u16 = 11;
+
+/// The rows of the derivation dialog, in the order they're drawn
+const FIELDS: [DerivationField; 8] = [
+    Site, Login, Counter, Length, Lowercase, Uppercase, Digits, Symbols,
+];
+
+/// Enum that contains all the rows of the derivation dialog
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DerivationField {
+    Site,
+    Login,
+    Counter,
+    Length,
+    Lowercase,
+    Uppercase,
+    Digits,
+    Symbols,
+}
+
+impl DerivationField {
+    /// Returns what the next field is
+    fn next(&self) -> DerivationField {
+        FIELDS[(self.line() as usize + 1) % FIELDS.len()]
+    }
+
+    /// Returns what the previous field is
+    fn prev(&self) -> DerivationField {
+        FIELDS[(self.line() as usize + FIELDS.len() - 1) % FIELDS.len()]
+    }
+
+    /// Returns the line the field is drawn on
+    fn line(&self) -> u16 {
+        FIELDS.iter().position(|field| field == self).unwrap_or(0) as u16
+    }
+
+    /// Returns the label shown next to the field
+    fn label(&self) -> &'static str {
+        match self {
+            Site => "Site",
+            Login => "Login",
+            Counter => "Counter",
+            Length => "Length",
+            Lowercase => "Lowercase",
+            Uppercase => "Uppercase",
+            Digits => "Digits",
+            Symbols => "Symbols",
+        }
+    }
+
+    /// Returns the value shown next to the field's label
+    fn value(&self, options: &DerivationOptions) -> String {
+        let checkbox = |checked: bool| match checked {
+            true => "[x]".to_string(),
+            false => "[ ]".to_string(),
+        };
+
+        match self {
+            Site => options.site().to_string(),
+            Login => options.login().to_string(),
+            Counter => options.counter().to_string(),
+            Length => options.length().to_string(),
+            Lowercase => checkbox(options.lowercase()),
+            Uppercase => checkbox(options.uppercase()),
+            Digits => checkbox(options.digits()),
+            Symbols => checkbox(options.symbols()),
+        }
+    }
+}
+
+/// Enum of what the user chose in the derivation dialog
+pub enum DeriveAction {
+    /// Derive the password with the options
+    Derive(DerivationOptions),
+    /// Stop deriving the password and store it instead
+    Stop,
+    /// Leave the account as it was
+    Cancel,
+}
+
+/// Entry point for the derivation dialog. Lets the user tweak what the password is derived from
+/// while previewing it
+///
+/// # Arguments
+///
+/// * `options`   - The options to start with
+/// * `policy`    - The site's password policy, which the derived password has to follow
+/// * `master`    - The master password derived passwords come from
+/// * `clipboard` - The clipboard, to show the status of
+///
+/// # Returns
+///
+/// What the user chose, or an IO error
+pub fn derive(
+    options: DerivationOptions,
+    policy: Option<&Policy>,
+    master: &str,
+    clipboard: &mut Clipboard,
+) -> Result<DeriveAction> {
+    let mut options = options;
+    let mut current_field = Site;
+    let mut password = preview(&options, policy, master);
+
+    loop {
+        draw_derive(&options, &password, current_field)?;
+        if let Event::Key(key) = clipboard.read_event()? {
+            match key.code {
+                KeyCode::Char('j') => current_field = current_field.next(),
+                KeyCode::Char('k') => current_field = current_field.prev(),
+                KeyCode::Char('y') if password.is_ok() => return Ok(DeriveAction::Derive(options)),
+                KeyCode::Char('x') => return Ok(DeriveAction::Stop),
+                KeyCode::Enter | KeyCode::Char(' ') | KeyCode::Char('e') => {
+                    edit(&mut options, current_field)?;
+                    password = preview(&options, policy, master);
+                }
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(DeriveAction::Cancel),
+                _ => (),
+            }
+        }
+    }
+}
+
+/// Derives the password the options give, so long as it follows the site's policy
+///
+/// # Arguments
+///
+/// * `options` - The options to derive with
+/// * `policy`  - The site's password policy, if it has one
+/// * `master`  - The master password derived passwords come from
+fn preview(
+    options: &DerivationOptions,
+    policy: Option<&Policy>,
+    master: &str,
+) -> std::result::Result<String, String> {
+    let password = options.derive(master.trim())?;
+    if let Some(policy) = policy {
+        policy
+            .check(&password)
+            .map_err(|e| format!("The site's policy says the password {}", e))?;
+    }
+    Ok(password)
+}
+
+/// Changes the given field of the options, toggling it or providing a textfield to edit it
+///
+/// # Arguments
+///
+/// * `options` - The options to change
+/// * `field`   - The field to change
+fn edit(options: &mut DerivationOptions, field: DerivationField) -> Result<()> {
+    match field {
+        Lowercase => options.set_lowercase(!options.lowercase()),
+        Uppercase => options.set_uppercase(!options.uppercase()),
+        Digits => options.set_digits(!options.digits()),
+        Symbols => options.set_symbols(!options.symbols()),
+        Site | Login | Counter | Length => {
+            execute!(stdout(), cursor::MoveTo(0, field.line()))?;
+            let new_value = textfield(
+                format!("{} ", box_label(field.label())),
+                (field.label().len() + 3) as u16,
+                field.value(options),
+                false,
+            )?;
+
+            if let Some(new_value) = new_value {
+                match field {
+                    Site => options.set_site(new_value),
+                    Login => options.set_login(new_value),
+                    Counter => {
+                        if let Ok(counter) = new_value.trim().parse() {
+                            options.set_counter(counter);
+                        }
+                    }
+                    _ => {
+                        if let Ok(length) = new_value.trim().parse() {
+                            options.set_length(length);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Draws the derivation dialog to the terminal
+///
+/// # Arguments
+///
+/// * `options`       - The options being edited
+/// * `password`      - The password the options derive, or why they couldn't
+/// * `current_field` - The current selected field
+fn draw_derive(
+    options: &DerivationOptions,
+    password: &std::result::Result<String, String>,
+    current_field: DerivationField,
+) -> Result<()> {
+    execute!(
+        stdout(),
+        cursor::MoveTo(0, 0),
+        Clear(ClearType::FromCursorDown),
+        cursor::Hide
+    )?;
+
+    for field in FIELDS {
+        println(format!(" {}  {}", field.label(), field.value(options)))?;
+    }
+
+    execute!(stdout(), cursor::MoveTo(0, PREVIEW_LINE))?;
+    match password {
+        Ok(password) => println(format!("{} {}", box_label("Preview"), password))?,
+        Err(error) => println(format!("{} {}", box_label("Error"), error))?,
+    }
+
+    execute!(stdout(), cursor::MoveTo(0, PROMPT_LINE))?;
+    println("ENTER change  y derive password  x store password instead  ESC cancel")?;
+
+    // Replacing the current fields normal label with the selected field version
+    execute!(stdout(), cursor::MoveTo(0, current_field.line()))?;
+    print(box_label(current_field.label()))?;
+
+    Ok(())
+}
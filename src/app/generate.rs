@@ -0,0 +1,464 @@
+//! This module contains the password generator dialog, where the user picks the options a random
+//! password is generated with
+
+use crossterm::{
+    cursor,
+    event::{Event, KeyCode},
+    execute,
+    terminal::{Clear, ClearType},
+    Result,
+};
+use std::io::stdout;
+use std::mem;
+
+use crate::clipboard::Clipboard;
+use crate::config::Config;
+use crate::generator::{Generator, GeneratorOptions, PassphraseOptions, PronounceableOptions};
+use crate::policy::Policy;
+use crate::terminal_drawing::{box_label, print, println, textfield};
+
+use super::notification::show_notification;
+
+use GeneratorField::*;
+
+/// The line the preview of the generated password is drawn on
+const PREVIEW_LINE: u16 = 11;
+/// The line the account's policy is drawn on
+const POLICY_LINE: u16 = 13;
+/// The line prompts are drawn on
+const PROMPT_LINE: u16 = 15;
+
+/// The rows shown when generating random characters
+const CHARACTER_FIELDS: [GeneratorField; 10] = [
+    Preset,
+    Mode,
+    Length,
+    Uppercase,
+    Lowercase,
+    Digits,
+    Symbols,
+    SymbolSet,
+    ExcludeLookalikes,
+    RequireEachClass,
+];
+
+/// The rows shown when generating a passphrase
+const PASSPHRASE_FIELDS: [GeneratorField; 6] =
+    [Preset, Mode, Words, Separator, Capitalise, IncludeDigit];
+
+/// The rows shown when generating a pronounceable password
+const PRONOUNCEABLE_FIELDS: [GeneratorField; 6] = [
+    Preset,
+    Mode,
+    Syllables,
+    Capitalise,
+    IncludeDigit,
+    IncludeSymbol,
+];
+
+/// Enum that contains all the rows of the generator dialog
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum GeneratorField {
+    Preset,
+    Mode,
+    Length,
+    Uppercase,
+    Lowercase,
+    Digits,
+    Symbols,
+    SymbolSet,
+    ExcludeLookalikes,
+    RequireEachClass,
+    Words,
+    Separator,
+    Syllables,
+    Capitalise,
+    IncludeDigit,
+    IncludeSymbol,
+}
+
+impl GeneratorField {
+    /// Returns the rows shown for the given generator, in the order they're drawn
+    fn fields(generator: &Generator) -> &'static [GeneratorField] {
+        match generator {
+            Generator::Characters(_) => &CHARACTER_FIELDS,
+            Generator::Passphrase(_) => &PASSPHRASE_FIELDS,
+            Generator::Pronounceable(_) => &PRONOUNCEABLE_FIELDS,
+        }
+    }
+
+    /// Returns what the next field is
+    fn next(&self, generator: &Generator) -> GeneratorField {
+        let fields = Self::fields(generator);
+        fields[(self.line(generator) as usize + 1) % fields.len()]
+    }
+
+    /// Returns what the previous field is
+    fn prev(&self, generator: &Generator) -> GeneratorField {
+        let fields = Self::fields(generator);
+        fields[(self.line(generator) as usize + fields.len() - 1) % fields.len()]
+    }
+
+    /// Returns the line the field is drawn on
+    fn line(&self, generator: &Generator) -> u16 {
+        Self::fields(generator)
+            .iter()
+            .position(|field| field == self)
+            .unwrap_or(0) as u16
+    }
+
+    /// Returns the label shown next to the field
+    fn label(&self) -> &'static str {
+        match self {
+            Preset => "Preset",
+            Mode => "Mode",
+            Length => "Length",
+            Uppercase => "Uppercase",
+            Lowercase => "Lowercase",
+            Digits => "Digits",
+            Symbols => "Symbols",
+            SymbolSet => "Symbol Set",
+            ExcludeLookalikes => "Exclude Look-alikes",
+            RequireEachClass => "One Of Each",
+            Words => "Words",
+            Separator => "Separator",
+            Syllables => "Syllables",
+            Capitalise => "Capitalise",
+            IncludeDigit => "Add A Digit",
+            IncludeSymbol => "Add A Symbol",
+        }
+    }
+
+    /// Returns the value shown next to the field's label
+    fn value(&self, generator: &Generator) -> String {
+        let checkbox = |checked: bool| match checked {
+            true => "[x]".to_string(),
+            false => "[ ]".to_string(),
+        };
+
+        match (self, generator) {
+            (Mode, Generator::Characters(_)) => "Characters".to_string(),
+            (Mode, Generator::Passphrase(_)) => "Passphrase".to_string(),
+            (Mode, Generator::Pronounceable(_)) => "Pronounceable".to_string(),
+            (Length, Generator::Characters(x)) => x.length().to_string(),
+            (Uppercase, Generator::Characters(x)) => checkbox(x.uppercase()),
+            (Lowercase, Generator::Characters(x)) => checkbox(x.lowercase()),
+            (Digits, Generator::Characters(x)) => checkbox(x.digits()),
+            (Symbols, Generator::Characters(x)) => checkbox(x.symbols()),
+            (SymbolSet, Generator::Characters(x)) => x.symbol_set().to_string(),
+            (ExcludeLookalikes, Generator::Characters(x)) => checkbox(x.exclude_lookalikes()),
+            (RequireEachClass, Generator::Characters(x)) => checkbox(x.require_each_class()),
+            (Words, Generator::Passphrase(x)) => x.words().to_string(),
+            (Separator, Generator::Passphrase(x)) => format!("\"{}\"", x.separator()),
+            (Capitalise, Generator::Passphrase(x)) => checkbox(x.capitalise()),
+            (IncludeDigit, Generator::Passphrase(x)) => checkbox(x.include_digit()),
+            (Syllables, Generator::Pronounceable(x)) => x.syllables().to_string(),
+            (Capitalise, Generator::Pronounceable(x)) => checkbox(x.capitalise()),
+            (IncludeDigit, Generator::Pronounceable(x)) => checkbox(x.include_digit()),
+            (IncludeSymbol, Generator::Pronounceable(x)) => checkbox(x.include_symbol()),
+            _ => String::new(),
+        }
+    }
+}
+
+/// Entry point for the generator dialog. Lets the user tweak the generator's options while
+/// previewing the passwords they produce
+///
+/// # Arguments
+///
+/// * `config`    - The user's config, where presets are loaded from and saved to
+/// * `policy`    - The policy every generated password follows, if the account has one
+/// * `clipboard` - The clipboard, to show the status of
+///
+/// # Returns
+///
+/// The generated password if the user accepted it, `None` if they cancelled, or an IO error
+pub fn generate(
+    config: &mut Config,
+    policy: Option<&Policy>,
+    clipboard: &mut Clipboard,
+) -> Result<Option<String>> {
+    let mut current_preset = 0;
+    // Every mode keeps its options so switching back and forth doesn't lose them
+    let mut generators = [
+        Generator::Characters(GeneratorOptions::default()),
+        Generator::Passphrase(PassphraseOptions::default()),
+        Generator::Pronounceable(PronounceableOptions::default()),
+    ];
+    let mut mode = 0;
+    if let Some((_, preset)) = config.presets().first() {
+        mode = load_preset(&mut generators, preset.clone());
+    }
+    let mut current_field = Preset;
+    let mut password = generate_password(&mut generators[mode], policy);
+
+    loop {
+        let generator = &generators[mode];
+        draw_generator(
+            config,
+            current_preset,
+            generator,
+            policy,
+            &password,
+            current_field,
+        )?;
+        if let Event::Key(key) = clipboard.read_event()? {
+            match key.code {
+                KeyCode::Char('j') => current_field = current_field.next(generator),
+                KeyCode::Char('k') => current_field = current_field.prev(generator),
+                KeyCode::Char('r') => (),
+                KeyCode::Char('s') => save_preset(config, generator)?,
+                KeyCode::Char('y') => {
+                    if let Ok(password) = password {
+                        return Ok(Some(password));
+                    }
+                }
+                KeyCode::Enter | KeyCode::Char(' ') | KeyCode::Char('e') => match current_field {
+                    Preset if !config.presets().is_empty() => {
+                        current_preset = (current_preset + 1) % config.presets().len();
+                        let preset = config.presets()[current_preset].1.clone();
+                        mode = load_preset(&mut generators, preset);
+                    }
+                    Preset => (),
+                    Mode => mode = (mode + 1) % generators.len(),
+                    field => edit(&mut generators[mode], field)?,
+                },
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(None),
+                _ => continue,
+            }
+            password = generate_password(&mut generators[mode], policy);
+        }
+    }
+}
+
+/// Generates a password that follows the policy. Character options are changed to fit the policy
+/// first, so they're always able to, while other modes are retried until they happen to
+///
+/// # Arguments
+///
+/// * `generator` - The generator to use
+/// * `policy`    - The policy the password must follow
+///
+/// # Returns
+///
+/// Either the password, or why one that follows the policy couldn't be generated
+fn generate_password(
+    generator: &mut Generator,
+    policy: Option<&Policy>,
+) -> std::result::Result<String, String> {
+    let Some(policy) = policy else {
+        return generator.generate();
+    };
+
+    if let Generator::Characters(options) = generator {
+        policy.constrain(options);
+    }
+    policy.retry(|| generator.generate())
+}
+
+/// Replaces the options of the preset's mode with the preset
+///
+/// # Arguments
+///
+/// * `generators` - The generator of every mode
+/// * `preset`     - The preset to load
+///
+/// # Returns
+///
+/// The index of the preset's mode
+fn load_preset(generators: &mut [Generator], preset: Generator) -> usize {
+    let mode = generators
+        .iter()
+        .position(|x| mem::discriminant(x) == mem::discriminant(&preset))
+        .unwrap_or(0);
+    generators[mode] = preset;
+    mode
+}
+
+/// Changes the given field of the generator, toggling it or providing a textfield to edit it
+///
+/// # Arguments
+///
+/// * `generator` - The generator to change
+/// * `field`     - The field to change
+fn edit(generator: &mut Generator, field: GeneratorField) -> Result<()> {
+    let line = field.line(generator);
+
+    match generator {
+        Generator::Characters(options) => match field {
+            Uppercase => options.set_uppercase(!options.uppercase()),
+            Lowercase => options.set_lowercase(!options.lowercase()),
+            Digits => options.set_digits(!options.digits()),
+            Symbols => options.set_symbols(!options.symbols()),
+            ExcludeLookalikes => options.set_exclude_lookalikes(!options.exclude_lookalikes()),
+            RequireEachClass => options.set_require_each_class(!options.require_each_class()),
+            Length => {
+                let new_value = edit_textfield(field, line, options.length().to_string())?;
+                if let Some(Ok(length)) = new_value.map(|x| x.trim().parse()) {
+                    options.set_length(length);
+                }
+            }
+            SymbolSet => {
+                let new_value = edit_textfield(field, line, options.symbol_set().to_string())?;
+                if let Some(new_value) = new_value {
+                    options.set_symbol_set(new_value);
+                }
+            }
+            _ => (),
+        },
+        Generator::Passphrase(options) => match field {
+            Capitalise => options.set_capitalise(!options.capitalise()),
+            IncludeDigit => options.set_include_digit(!options.include_digit()),
+            Words => {
+                let new_value = edit_textfield(field, line, options.words().to_string())?;
+                if let Some(Ok(words)) = new_value.map(|x| x.trim().parse()) {
+                    options.set_words(words);
+                }
+            }
+            Separator => {
+                let new_value = edit_textfield(field, line, options.separator().to_string())?;
+                if let Some(new_value) = new_value {
+                    options.set_separator(new_value);
+                }
+            }
+            _ => (),
+        },
+        Generator::Pronounceable(options) => match field {
+            Capitalise => options.set_capitalise(!options.capitalise()),
+            IncludeDigit => options.set_include_digit(!options.include_digit()),
+            IncludeSymbol => options.set_include_symbol(!options.include_symbol()),
+            Syllables => {
+                let new_value = edit_textfield(field, line, options.syllables().to_string())?;
+                if let Some(Ok(syllables)) = new_value.map(|x| x.trim().parse()) {
+                    options.set_syllables(syllables);
+                }
+            }
+            _ => (),
+        },
+    }
+    Ok(())
+}
+
+/// Provides a textfield over a field's row to edit its value
+///
+/// # Arguments
+///
+/// * `field`   - The field being edited
+/// * `line`    - The line the field is drawn on
+/// * `content` - The current value of the field
+///
+/// # Returns
+///
+/// The new value, or `None` if the user cancelled
+fn edit_textfield(field: GeneratorField, line: u16, content: String) -> Result<Option<String>> {
+    execute!(stdout(), cursor::MoveTo(0, line))?;
+    textfield(
+        format!("{} ", box_label(field.label())),
+        (field.label().len() + 3) as u16,
+        content,
+        false,
+    )
+}
+
+/// Prompts the user for a name and saves the generator as a preset under it
+///
+/// # Arguments
+///
+/// * `config`    - The config to save the preset to
+/// * `generator` - The generator to save
+fn save_preset(config: &mut Config, generator: &Generator) -> Result<()> {
+    execute!(
+        stdout(),
+        cursor::MoveTo(0, PROMPT_LINE),
+        Clear(ClearType::FromCursorDown)
+    )?;
+
+    let name = textfield(
+        format!("{} ", box_label("Preset Name")),
+        14,
+        String::new(),
+        false,
+    )?;
+
+    if let Some(name) = name.map(|x| x.trim().to_string()) {
+        if name.is_empty() || name.contains(['=', ' ']) {
+            return show_notification("Preset names can't be empty or contain spaces or '='");
+        }
+        if let Err(e) = config.save_preset(&name, generator.clone()) {
+            return show_notification(format!("Couldn't save the preset: {}", e));
+        }
+    }
+
+    Ok(())
+}
+
+/// Draws the generator dialog to the terminal
+///
+/// # Arguments
+///
+/// * `config`         - The config holding the presets
+/// * `current_preset` - The index of the last loaded preset
+/// * `generator`      - The generator being edited
+/// * `policy`         - The policy of the account the password is for
+/// * `password`       - The password the generator produced, or why it couldn't
+/// * `current_field`  - The current selected field
+fn draw_generator(
+    config: &Config,
+    current_preset: usize,
+    generator: &Generator,
+    policy: Option<&Policy>,
+    password: &std::result::Result<String, String>,
+    current_field: GeneratorField,
+) -> Result<()> {
+    execute!(
+        stdout(),
+        cursor::MoveTo(0, 0),
+        Clear(ClearType::FromCursorDown),
+        cursor::Hide
+    )?;
+
+    let preset = match config.presets().get(current_preset) {
+        Some((name, _)) => format!(
+            "{} ({}/{})",
+            name,
+            current_preset + 1,
+            config.presets().len()
+        ),
+        None => "None saved".to_string(),
+    };
+
+    for field in GeneratorField::fields(generator) {
+        let value = match field {
+            Preset => preset.clone(),
+            field => field.value(generator),
+        };
+        println(format!(" {}  {}", field.label(), value))?;
+    }
+
+    execute!(stdout(), cursor::MoveTo(0, PREVIEW_LINE))?;
+    match password {
+        Ok(password) => {
+            println(format!("{} {}", box_label("Preview"), password))?;
+            println(format!(
+                "{} {:.0} bits",
+                box_label("Entropy"),
+                generator.entropy_bits()
+            ))?;
+        }
+        Err(error) => println(format!("{} {}", box_label("Error"), error))?,
+    }
+
+    if let Some(policy) = policy {
+        execute!(stdout(), cursor::MoveTo(0, POLICY_LINE))?;
+        println(format!(" Policy  {}", policy.summary()))?;
+    }
+
+    execute!(stdout(), cursor::MoveTo(0, PROMPT_LINE))?;
+    println("ENTER change  r regenerate  s save preset  y use password  ESC cancel")?;
+
+    // Replacing the current fields normal label with the selected field version
+    execute!(stdout(), cursor::MoveTo(0, current_field.line(generator)))?;
+    print(box_label(current_field.label()))?;
+
+    Ok(())
+}
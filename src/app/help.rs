@@ -20,9 +20,51 @@ pub fn show_help_menu() -> Result<()> {
         ("ESC", "Exit"),
         ("ENTER", "Select"),
         ("", ""),
+        ("Search", ""),
+        ("UP", "Highlight previous result"),
+        ("DOWN", "Highlight next result"),
+        ("C-p", "Yank password"),
+        ("C-u", "Yank username"),
+        ("C-e", "Yank email"),
+        ("C-y", "Yank password and quit"),
+        ("", ""),
         ("Account View", ""),
         ("e", "Edit field"),
-        ("G", "Generate random password"),
+        (
+            "G",
+            "Start rotating to a generated password, or a derived password's next counter",
+        ),
+        ("Y", "Yank the pending password"),
+        ("c", "Confirm the pending password was changed on the site"),
+        ("X", "Cancel the rotation, keeping the current password"),
+        (
+            "S",
+            "Set the script that rotates the password, and how often",
+        ),
+        ("R", "Rotate the password with its script now"),
+        (
+            "E",
+            "Set when the password expires, as a date or number of days",
+        ),
+        (
+            "T",
+            "Make the account temporary, moving it to the trash after a date",
+        ),
+        ("d", "Derive the password instead of storing it"),
+        ("P", "Edit the site's password policy"),
+        ("u", "List every account using the same password"),
+        ("D", "Move the account to the trash"),
+        ("H", "Show previous passwords to reveal, yank or restore"),
+        ("", ""),
+        ("Trash", ""),
+        ("r", "Restore the account"),
+        ("D", "Delete the account forever"),
+        ("", ""),
+        ("Generator", ""),
+        ("ENTER", "Toggle or change option"),
+        ("r", "Regenerate password"),
+        ("s", "Save options as a preset"),
+        ("y", "Use password"),
     ];
 
     execute!(stdout(), Clear(ClearType::All), cursor::MoveTo(0, 0))?;
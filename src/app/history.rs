@@ -0,0 +1,122 @@
+//! This module contains the password history page, which lists an account's previous passwords so
+//! one can be revealed, yanked or restored
+
+use colored::Colorize;
+use crossterm::{
+    cursor,
+    event::{Event, KeyCode},
+    execute,
+    terminal::{Clear, ClearType},
+    Result,
+};
+use std::io::stdout;
+
+use crate::account::{Account, PastPassword};
+use crate::clipboard::Clipboard;
+use crate::terminal_drawing::{box_label, get_confirmation, println};
+use crate::time::format_utc;
+
+/// Enum of what the user chose on the history page
+pub enum HistoryAction {
+    /// Make the password at the given position in the history the account's password again
+    Restore(usize),
+    /// Go back to the account
+    Exit,
+}
+
+/// Entry point for the history page. Lists the account's previous passwords hidden, until the
+/// user reveals the selected one
+///
+/// # Arguments
+///
+/// * `account`   - The account to show the history of
+/// * `clipboard` - The clipboard to yank passwords into
+///
+/// # Returns
+///
+/// Either what the user chose, or an IO error
+pub fn show_history(account: &Account, clipboard: &mut Clipboard) -> Result<HistoryAction> {
+    let history = account.history();
+    let mut selected = 0;
+    let mut revealed = false;
+
+    loop {
+        draw_history(account, &history, selected, revealed)?;
+        if let Event::Key(key) = clipboard.read_event()? {
+            match key.code {
+                KeyCode::Char('j') | KeyCode::Down if selected + 1 < history.len() => {
+                    selected += 1;
+                    revealed = false;
+                }
+                KeyCode::Char('k') | KeyCode::Up if selected > 0 => {
+                    selected -= 1;
+                    revealed = false;
+                }
+                KeyCode::Char('r') => revealed = !revealed,
+                KeyCode::Char('y') => {
+                    if let Some(past) = history.get(selected) {
+                        clipboard.yank(past.password.clone())?;
+                    }
+                }
+                KeyCode::Enter if selected < history.len() => {
+                    println("")?;
+                    println("Restore this password? The current one is kept in the history [y/N]")?;
+                    if get_confirmation()? {
+                        return Ok(HistoryAction::Restore(selected));
+                    }
+                }
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(HistoryAction::Exit),
+                _ => (),
+            }
+        }
+    }
+}
+
+/// Draws the history page to the terminal
+///
+/// # Arguments
+///
+/// * `account`  - The account the history belongs to
+/// * `history`  - The account's previous passwords, newest first
+/// * `selected` - The position of the selected password
+/// * `revealed` - Whether the selected password is shown instead of `*`s
+fn draw_history(
+    account: &Account,
+    history: &[PastPassword],
+    selected: usize,
+    revealed: bool,
+) -> Result<()> {
+    execute!(
+        stdout(),
+        cursor::MoveTo(0, 0),
+        Clear(ClearType::FromCursorDown),
+        cursor::Hide
+    )?;
+
+    println(format!(
+        "{} {}",
+        box_label("Password History"),
+        account.label()
+    ))?;
+    if history.is_empty() {
+        println("No previous passwords")?;
+    }
+
+    for (position, past) in history.iter().enumerate() {
+        let password = match position == selected && revealed {
+            true => past.password.clone(),
+            false => past.password.chars().map(|_| '*').collect(),
+        };
+        println(format!(
+            "{} {}  {}",
+            if position == selected { '>' } else { ' ' },
+            password,
+            format!("replaced {}", format_utc(past.replaced)).dimmed()
+        ))?;
+    }
+
+    println("")?;
+    println("j/k move  r reveal  y yank  ENTER restore  ESC back")?;
+
+    Ok(())
+}
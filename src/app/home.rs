@@ -1,8 +1,9 @@
 //! This module contains the home page of the application
 
+use colored::Colorize;
 use crossterm::{
     cursor,
-    event::{read, Event, KeyCode},
+    event::{Event, KeyCode},
     execute,
     terminal::{Clear, ClearType},
     Result,
@@ -10,13 +11,21 @@ use crossterm::{
 
 use std::io::stdout;
 
+use crate::account::Account;
+use crate::clipboard::Clipboard;
 use crate::terminal_drawing::{box_label, print, println};
+use crate::time::format_date;
 
 use PageOption::*;
 
 #[derive(Clone, Copy)]
 pub enum PageOption {
     Search,
+    Lookup,
+    Audit,
+    Breaches,
+    RotateDue,
+    Trash,
     ChangePassword,
     Help,
     Exit,
@@ -25,7 +34,12 @@ pub enum PageOption {
 impl PageOption {
     pub fn next(&self) -> Self {
         match self {
-            Search => ChangePassword,
+            Search => Lookup,
+            Lookup => Audit,
+            Audit => Breaches,
+            Breaches => RotateDue,
+            RotateDue => Trash,
+            Trash => ChangePassword,
             ChangePassword => Help,
             Help => Exit,
             Exit => Search,
@@ -35,7 +49,12 @@ impl PageOption {
     pub fn prev(&self) -> Self {
         match self {
             Search => Exit,
-            ChangePassword => Search,
+            Lookup => Search,
+            Audit => Lookup,
+            Breaches => Audit,
+            RotateDue => Breaches,
+            Trash => RotateDue,
+            ChangePassword => Trash,
             Help => ChangePassword,
             Exit => Help,
         }
@@ -44,12 +63,17 @@ impl PageOption {
 
 /// Entry point for the home page of the application. Allows the user to decide what page they'd
 /// like to enter
-pub fn home() -> crossterm::Result<PageOption> {
+///
+/// # Arguments
+///
+/// * `accounts`  - The accounts in the vault, to list the ones being rotated
+/// * `clipboard` - The clipboard, to show the status of
+pub fn home(accounts: &[Account], clipboard: &mut Clipboard) -> crossterm::Result<PageOption> {
     let mut current_option = PageOption::Search;
 
     loop {
-        draw_home(current_option)?;
-        if let Event::Key(key) = read()? {
+        draw_home(accounts, current_option)?;
+        if let Event::Key(key) = clipboard.read_event()? {
             match key.code {
                 KeyCode::Char('j') => current_option = current_option.next(),
                 KeyCode::Char('k') => current_option = current_option.prev(),
@@ -63,7 +87,7 @@ pub fn home() -> crossterm::Result<PageOption> {
     Ok(current_option)
 }
 
-fn draw_home(current_option: PageOption) -> Result<()> {
+fn draw_home(accounts: &[Account], current_option: PageOption) -> Result<()> {
     execute!(
         stdout(),
         cursor::MoveTo(0, 0),
@@ -73,19 +97,49 @@ fn draw_home(current_option: PageOption) -> Result<()> {
 
     println(format!("Password Manager v{}", env!("CARGO_PKG_VERSION")))?;
     println(" Search Accounts ")?;
+    println(" Password Lookup ")?;
+    println(" Audit ")?;
+    println(" Breach Report ")?;
+    println(" Rotate Due Passwords ")?;
+    println(" Trash ")?;
     println(" Change Password ")?;
     println(" Help ")?;
     println(" Exit ")?;
 
+    // Rotations are listed until they're confirmed, so a half finished one isn't forgotten
+    let rotating: Vec<&Account> = accounts.iter().filter(|x| x.is_rotating()).collect();
+    if !rotating.is_empty() {
+        println("")?;
+        println("Pending rotations".bold())?;
+        for account in rotating {
+            let started = account.rotation_started().map(format_date);
+            println(format!(
+                " {}  {}",
+                account.label(),
+                format!("started {}", started.unwrap_or_default()).dimmed()
+            ))?;
+        }
+    }
+
     let current_option_line = match current_option {
         Search => 1,
-        ChangePassword => 2,
-        Help => 3,
-        Exit => 4,
+        Lookup => 2,
+        Audit => 3,
+        Breaches => 4,
+        RotateDue => 5,
+        Trash => 6,
+        ChangePassword => 7,
+        Help => 8,
+        Exit => 9,
     };
 
     let selected_text = match current_option {
         Search => box_label("Search Accounts"),
+        Lookup => box_label("Password Lookup"),
+        Audit => box_label("Audit"),
+        Breaches => box_label("Breach Report"),
+        RotateDue => box_label("Rotate Due Passwords"),
+        Trash => box_label("Trash"),
         ChangePassword => box_label("Change Password"),
         Help => box_label("Help"),
         Exit => box_label("Exit"),
@@ -0,0 +1,110 @@
+//! This module contains the password lookup page, which lists every account using a password so
+//! it can be changed everywhere once it's compromised
+
+use colored::Colorize;
+use crossterm::{
+    cursor,
+    event::{Event, KeyCode},
+    execute,
+    terminal::{Clear, ClearType},
+    Result,
+};
+use std::io::stdout;
+
+use crate::account::Account;
+use crate::clipboard::Clipboard;
+use crate::terminal_drawing::{box_label, println, textfield};
+
+/// Enum of what the user chose on the lookup page
+pub enum LookupAction {
+    /// View the account at the given position in the vault
+    ViewAccount(usize),
+    /// Go back to where the lookup was started from
+    Exit,
+}
+
+/// Asks the user for the password to look up, hiding it as it's typed
+///
+/// # Returns
+///
+/// `None` if the user cancels, otherwise the password, or an IO error
+pub fn prompt_password() -> Result<Option<String>> {
+    execute!(
+        stdout(),
+        cursor::MoveTo(0, 0),
+        Clear(ClearType::FromCursorDown)
+    )?;
+    let prompt = format!("{} ", box_label("Password to look up"));
+    textfield(prompt, 22, String::new(), true)
+}
+
+/// Lists the accounts using a password, letting the user pick one to view
+///
+/// # Arguments
+///
+/// * `accounts`  - The accounts in the vault
+/// * `using`     - The indices of the accounts using the password
+/// * `clipboard` - The clipboard, to show the status of
+pub fn show_accounts_using(
+    accounts: &[Account],
+    using: &[usize],
+    clipboard: &mut Clipboard,
+) -> Result<LookupAction> {
+    let mut selected = 0;
+
+    loop {
+        draw_lookup(accounts, using, selected)?;
+        if let Event::Key(key) = clipboard.read_event()? {
+            match key.code {
+                KeyCode::Char('j') | KeyCode::Down if selected + 1 < using.len() => selected += 1,
+                KeyCode::Char('k') | KeyCode::Up => selected = selected.saturating_sub(1),
+                KeyCode::Enter => {
+                    if let Some(index) = using.get(selected) {
+                        return Ok(LookupAction::ViewAccount(*index));
+                    }
+                }
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(LookupAction::Exit),
+                _ => (),
+            }
+        }
+    }
+}
+
+/// Draws the lookup page to the terminal
+///
+/// # Arguments
+///
+/// * `accounts` - The accounts in the vault
+/// * `using`    - The indices of the accounts using the password
+/// * `selected` - The position of the selected account in `using`
+fn draw_lookup(accounts: &[Account], using: &[usize], selected: usize) -> Result<()> {
+    execute!(
+        stdout(),
+        cursor::MoveTo(0, 0),
+        Clear(ClearType::FromCursorDown),
+        cursor::Hide
+    )?;
+
+    println(box_label("Password Lookup"))?;
+    match using.len() {
+        0 => println("No accounts use this password")?,
+        1 => println("1 account uses this password")?,
+        count => println(format!("{} accounts use this password", count))?,
+    }
+
+    for (position, index) in using.iter().enumerate() {
+        let account = &accounts[*index];
+        let login = account.username().or(account.email()).unwrap_or_default();
+        println(format!(
+            "{} {}  {}",
+            if position == selected { '>' } else { ' ' },
+            account.label(),
+            login.dimmed()
+        ))?;
+    }
+
+    println("")?;
+    println("j/k move  ENTER view account  ESC back")?;
+
+    Ok(())
+}
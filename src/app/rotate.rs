@@ -0,0 +1,83 @@
+//! This module contains the rotate due page, which runs the rotation script of every account whose
+//! password is due to be rotated and shows how each one went
+
+use colored::Colorize;
+use crossterm::{
+    cursor,
+    event::Event,
+    execute,
+    terminal::{Clear, ClearType},
+    Result,
+};
+use std::io::stdout;
+
+use crate::account::Account;
+use crate::clipboard::Clipboard;
+use crate::rotation::{due, rotate};
+use crate::terminal_drawing::{box_label, print, println};
+use crate::time;
+
+use super::Vault;
+
+/// Rotates every account that's due with its script, one after another, then shows a summary
+/// until the user presses a key. The vault is saved after each successful rotation, as the script
+/// has already changed the password on the service
+///
+/// # Arguments
+///
+/// * `accounts`  - The accounts in the vault
+/// * `trash`     - The accounts in the trash, which are saved with the vault
+/// * `vault`     - Where the vault is saved and the password to it
+/// * `clipboard` - The clipboard, to show the status of
+pub fn rotate_due(
+    accounts: &mut [Account],
+    trash: &[Account],
+    vault: &Vault,
+    clipboard: &mut Clipboard,
+) -> Result<()> {
+    execute!(
+        stdout(),
+        cursor::MoveTo(0, 0),
+        Clear(ClearType::FromCursorDown),
+        cursor::Hide
+    )?;
+    println(box_label("Rotate Due Passwords"))?;
+
+    let due = due(accounts, time::now());
+    if due.is_empty() {
+        println("No passwords with a rotation script are due")?;
+    }
+
+    let mut failed = 0;
+    for index in &due {
+        let label = accounts[*index].label();
+        print(format!(" {}  {}", label, "rotating...".dimmed()))?;
+        let result = rotate(&mut accounts[*index], vault.password, time::now());
+
+        execute!(
+            stdout(),
+            cursor::MoveToColumn(0),
+            Clear(ClearType::CurrentLine)
+        )?;
+        match result {
+            Ok(()) => {
+                vault.save(accounts, trash)?;
+                println(format!(" {}  {}", label, "rotated".green()))?;
+            }
+            Err(error) => {
+                failed += 1;
+                println(format!(" {}  {}", label, error.red()))?;
+            }
+        }
+    }
+
+    if !due.is_empty() {
+        println("")?;
+        println(format!("{} rotated, {} failed", due.len() - failed, failed))?;
+    }
+    println("")?;
+    println("Press any key to go back")?;
+
+    while !matches!(clipboard.read_event()?, Event::Key(_)) {}
+    Ok(())
+}
@@ -1,8 +1,11 @@
 //! This module contains the search "page" of the application
+mod index;
+mod query;
 
+use colored::Colorize;
 use crossterm::{
     cursor,
-    event::{read, Event, KeyCode},
+    event::{Event, KeyCode, KeyModifiers},
     execute,
     style::Print,
     terminal::{Clear, ClearType},
@@ -10,81 +13,458 @@ use crossterm::{
 };
 use std::io::stdout;
 
-use crate::account::Account;
+use crate::account::{describe_expiry, Account};
+use crate::clipboard::Clipboard;
+use crate::config::Config;
+use crate::strength::estimate;
 use crate::terminal_drawing;
+use crate::time::{self, DAY};
+
+use super::view::{yank_current_field, AccountField};
+
+use index::IndexedAccount;
+use query::Query;
+
+pub use index::SearchIndex;
+
+/// Score given to every character of the query that is matched
+const SCORE_MATCH: i64 = 16;
+/// Bonus for matching the very first character of the text
+const BONUS_PREFIX: i64 = 24;
+/// Bonus for matching the first character of a word
+const BONUS_BOUNDARY: i64 = 12;
+/// Bonus for matching the character directly after the previous match
+const BONUS_CONSECUTIVE: i64 = 16;
+/// Penalty for every unmatched character skipped between two matches
+const PENALTY_GAP: i64 = 1;
+
+/// The fields of an account that can be searched, and how much weight a match in each is given
+const SEARCH_FIELDS: [(SearchField, i64); 6] = [
+    (SearchField::Label, 100),
+    (SearchField::Username, 80),
+    (SearchField::Email, 70),
+    (SearchField::Url, 60),
+    (SearchField::Tags, 50),
+    (SearchField::Notes, 30),
+];
 
 /// Enum that represents the result of the search page
 pub enum SearchAction {
     /// Create a new account with the given label
     NewAccount(String),
-    /// View an existing account with the given label
-    ViewAccount(String),
-    /// Exit the application
+    /// View the existing account at the given position in the vault
+    ViewAccount(usize),
+    /// Exit the search
     Exit,
+    /// Quit the password manager entirely
+    Quit,
+}
+
+/// The result of fuzzy matching a query against some text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// How well the query matched, higher is better
+    score: i64,
+    /// The char indices of the text that were matched
+    indices: Vec<usize>,
+}
+
+impl FuzzyMatch {
+    /// Returns the score of the match
+    pub fn score(&self) -> i64 {
+        self.score
+    }
+
+    /// Returns the char indices of the text that the query matched
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+}
+
+/// Enum of the non-secret account fields that the search looks through
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SearchField {
+    Label,
+    Username,
+    Email,
+    Url,
+    Tags,
+    Notes,
+}
+
+impl SearchField {
+    /// Returns the value of this field in the given account, if it has one
+    fn value(&self, account: &Account) -> Option<String> {
+        match self {
+            SearchField::Label => Some(account.label()),
+            SearchField::Username => account.username(),
+            SearchField::Email => account.email(),
+            SearchField::Url => account.url(),
+            SearchField::Tags => Some(account.tags().join(", ")).filter(|tags| !tags.is_empty()),
+            SearchField::Notes => account.notes(),
+        }
+    }
+
+    /// Returns where the field is in `SEARCH_FIELDS`
+    fn position(&self) -> usize {
+        match self {
+            SearchField::Label => 0,
+            SearchField::Username => 1,
+            SearchField::Email => 2,
+            SearchField::Url => 3,
+            SearchField::Tags => 4,
+            SearchField::Notes => 5,
+        }
+    }
+
+    /// Returns the short name of the field shown in the search results
+    fn name(&self) -> &'static str {
+        match self {
+            SearchField::Label => "label",
+            SearchField::Username => "user",
+            SearchField::Email => "email",
+            SearchField::Url => "url",
+            SearchField::Tags => "tags",
+            SearchField::Notes => "notes",
+        }
+    }
+}
+
+/// Which field of an account the search term matched, and how well
+#[derive(Debug, Clone)]
+pub struct AccountMatch {
+    /// The field that matched best
+    field: SearchField,
+    /// How the search term matched the field
+    matched: FuzzyMatch,
+    /// The score of the match after the field's weighting was applied
+    score: i64,
 }
 
 /// Entry point for searching the saved accounts
-pub fn search(accounts: &[Account]) -> Result<SearchAction> {
+///
+/// # Arguments
+///
+/// * `accounts`  - The accounts in the vault
+/// * `index`     - The search index over the vault's accounts
+/// * `master`    - The master password derived passwords come from
+/// * `config`    - The user's config, for when passwords count as weak or expiring soon
+/// * `clipboard` - The clipboard to yank fields into
+pub fn search(
+    accounts: &mut [Account],
+    index: &SearchIndex,
+    master: &str,
+    config: &Config,
+    clipboard: &mut Clipboard,
+) -> Result<SearchAction> {
+    let warn_days = config.expiry_warning_days();
     let mut search_term = String::new();
-    let mut filtered_accounts = accounts.to_owned();
-    let mut search_over = false;
+    let mut query = Query::default();
+    // Working out every password's strength is slow with derived passwords, so it's only done
+    // once a query needs it
+    let mut weak: Vec<bool> = vec![];
+    let mut filtered_accounts = Ok(find(accounts, index, &query, &weak));
+    let mut selected = 0;
 
     loop {
-        draw_search_results(&filtered_accounts)?;
+        draw_search_results(index, accounts, &filtered_accounts, selected, warn_days)?;
         let prompt = format!("{} ", terminal_drawing::box_label("Search"));
-        search_term = match search_textfield(prompt, 9, search_term)? {
-            SearchResult::ContinueSearch(search_term) => search_term,
-            SearchResult::SearchFinished(search_term) => {
-                search_over = true;
-                search_term
+        let search_result = search_textfield(prompt, 9, &mut search_term, clipboard)?;
+
+        let results = filtered_accounts.as_deref().unwrap_or_default();
+        let highlighted = results
+            .get(selected)
+            .map(|(position, _)| &mut accounts[*position]);
+
+        match search_result {
+            SearchResult::ContinueSearch => {
+                filtered_accounts = query::parse(&search_term).map(|parsed_query| {
+                    query = parsed_query;
+                    if query.checks_strength() && weak.is_empty() {
+                        let min_score = config.audit_options().min_score();
+                        weak = weak_passwords(accounts, master, min_score);
+                    }
+                    find(accounts, index, &query, &weak)
+                });
+                selected = 0;
             }
+            SearchResult::SelectPrevious => selected = selected.saturating_sub(1),
+            SearchResult::SelectNext if selected + 1 < results.len() => selected += 1,
+            SearchResult::SelectNext => (),
+            SearchResult::Yank(field) => {
+                if let Some(account) = highlighted {
+                    yank_current_field(account, field, master, clipboard)?;
+                }
+            }
+            SearchResult::YankAndQuit => {
+                if let Some(account) = highlighted {
+                    yank_current_field(account, AccountField::Password, master, clipboard)?;
+                    return Ok(SearchAction::Quit);
+                }
+            }
+            // Only finishing the search once there's a valid query, and only offering to create
+            // a new account when the query could be used as its label
+            SearchResult::SearchFinished => match results.get(selected) {
+                Some((position, _)) => return Ok(SearchAction::ViewAccount(*position)),
+                None if filtered_accounts.is_ok() && query.is_plain_text() => {
+                    return Ok(SearchAction::NewAccount(search_term))
+                }
+                None => (),
+            },
             SearchResult::Exit => return Ok(SearchAction::Exit),
+        }
+    }
+}
+
+/// Finds the accounts matching the query, sorted by how well they match unless the query sorts
+/// them by time. Accounts without the time being sorted by come last, or first when the oldest
+/// come first
+///
+/// # Arguments
+///
+/// * `accounts` - The accounts in the vault, for their passwords' ages and the times they're sorted
+///   by
+/// * `index`    - The search index over the vault's accounts
+/// * `query`    - The parsed contents of the search box
+/// * `weak`     - Whether each account's password is weak, if the query needs to know
+fn find(
+    accounts: &[Account],
+    index: &SearchIndex,
+    query: &Query,
+    weak: &[bool],
+) -> Vec<(usize, AccountMatch)> {
+    let now = time::now();
+    let mut results = index.search(query);
+    results.retain(|(position, _)| {
+        let is_weak = weak.get(*position).copied().unwrap_or_default();
+        query.matches_password(&accounts[*position], is_weak, now)
+    });
+
+    match query.sort() {
+        Some((key, false)) => {
+            results.sort_by_key(|(position, _)| std::cmp::Reverse(key.time(&accounts[*position])))
+        }
+        Some((key, true)) => results.sort_by_key(|(position, _)| key.time(&accounts[*position])),
+        None => (),
+    }
+
+    results
+}
+
+/// Returns whether each account's password is below the strength threshold. Empty passwords and
+/// passwords that can't be derived don't count as weak, like in the audit
+///
+/// # Arguments
+///
+/// * `accounts`  - The accounts in the vault
+/// * `master`    - The master password derived passwords come from
+/// * `min_score` - The strength score passwords below are weak
+fn weak_passwords(accounts: &[Account], master: &str, min_score: u8) -> Vec<bool> {
+    accounts
+        .iter()
+        .map(|account| match account.resolve_password(master) {
+            Ok(password) if !password.is_empty() => estimate(&password).score() < min_score,
+            _ => false,
+        })
+        .collect()
+}
+
+/// Matches each free text term against every searchable field of the account on its own, so the
+/// terms can match different fields in any order
+///
+/// # Arguments
+///
+/// * `account` - The indexed account to match against
+/// * `terms`   - The free text terms the user has typed into the search box
+///
+/// # Returns
+///
+/// `None` if any term didn't match, otherwise the field the best term matched with the matches of
+/// every term in that field, scored by adding up the score of each term. No terms always match the
+/// label
+fn match_account(account: &IndexedAccount, terms: &[String]) -> Option<AccountMatch> {
+    if terms.is_empty() {
+        return match_term(account, "");
+    }
+
+    let matches: Vec<AccountMatch> = terms
+        .iter()
+        .map(|term| match_term(account, term))
+        .collect::<Option<_>>()?;
+
+    let score = matches.iter().map(|x| x.score).sum();
+    let field = matches.iter().max_by_key(|x| x.score)?.field;
+    let mut indices: Vec<usize> = matches
+        .iter()
+        .filter(|x| x.field == field)
+        .flat_map(|x| x.matched.indices().iter().copied())
+        .collect();
+    indices.sort_unstable();
+    indices.dedup();
+
+    Some(AccountMatch {
+        field,
+        matched: FuzzyMatch { score, indices },
+        score,
+    })
+}
+
+/// Matches a single term against every searchable field of the account, weighting each field's
+/// score so that, for example, a label match beats an equally good email match
+///
+/// # Arguments
+///
+/// * `account` - The indexed account to match against
+/// * `term`    - One free text term from the search box
+///
+/// # Returns
+///
+/// `None` if no field matched, otherwise the best weighted match. An empty term always matches
+/// the label
+fn match_term(account: &IndexedAccount, term: &str) -> Option<AccountMatch> {
+    let mut best: Option<AccountMatch> = None;
+
+    for (field, weight) in SEARCH_FIELDS {
+        let Some(value) = account.field(field) else {
+            continue;
         };
-        if search_term.is_empty() && !search_over {
-            filtered_accounts = accounts.to_owned();
+        let Some(matched) = fuzzy_match(term, value) else {
             continue;
+        };
+        let score = matched.score() * weight / 100;
+        if best.as_ref().is_none_or(|best| score > best.score) {
+            best = Some(AccountMatch {
+                field,
+                matched,
+                score,
+            });
         }
-        // This algorithim really needs some work... DON'T FORGET TO CHANGE THIS
-        filtered_accounts = accounts
-            .iter()
-            .filter(|x| {
-                for letter in search_term.chars() {
-                    if !x.label().contains(letter) {
-                        return false;
-                    }
-                }
-                true
-            })
-            .map(|x| x.to_owned())
-            .collect();
+    }
 
-        if search_over {
-            break;
+    best
+}
+
+/// Case insensitively matches the query against the text as a subsequence, scoring the best
+/// possible alignment. Matches at the start of the text, at the start of words, and runs of
+/// consecutive characters score higher, while gaps between matched characters are penalised
+///
+/// # Arguments
+///
+/// * `query` - What to look for
+/// * `text`  - The text to look in
+///
+/// # Returns
+///
+/// `None` if the characters of the query do not all appear in order in the text, otherwise the
+/// score of the match and which characters were matched
+pub fn fuzzy_match(query: &str, text: &str) -> Option<FuzzyMatch> {
+    let query: Vec<char> = query.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    if query.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            indices: vec![],
+        });
+    }
+
+    if query.len() > text.len() {
+        return None;
+    }
+
+    let chars_eq = |a: char, b: char| a.to_lowercase().eq(b.to_lowercase());
+
+    // The score gained by matching a query character at the given position of the text
+    let char_score = |j: usize| {
+        let mut score = SCORE_MATCH;
+        if j == 0 {
+            score += BONUS_PREFIX;
+        } else if !text[j - 1].is_alphanumeric()
+            || (text[j - 1].is_lowercase() && text[j].is_uppercase())
+        {
+            score += BONUS_BOUNDARY;
+        }
+        score
+    };
+
+    // best[i][j] is the best score for matching query[..=i] with query[i] matched at text[j],
+    // with prev[i][j] storing where query[i - 1] was matched to get that score
+    let mut best: Vec<Vec<Option<i64>>> = vec![vec![None; text.len()]; query.len()];
+    let mut prev: Vec<Vec<usize>> = vec![vec![0; text.len()]; query.len()];
+
+    for (j, &c) in text.iter().enumerate() {
+        if chars_eq(query[0], c) {
+            best[0][j] = Some(char_score(j) - PENALTY_GAP * j as i64);
         }
     }
-    match filtered_accounts.first() {
-        Some(account) => Ok(SearchAction::ViewAccount(account.label())),
-        None => Ok(SearchAction::NewAccount(search_term)),
+
+    for i in 1..query.len() {
+        for j in i..text.len() {
+            if !chars_eq(query[i], text[j]) {
+                continue;
+            }
+            for k in (i - 1)..j {
+                let Some(previous) = best[i - 1][k] else {
+                    continue;
+                };
+                let mut score = previous + char_score(j);
+                if k + 1 == j {
+                    score += BONUS_CONSECUTIVE;
+                } else {
+                    score -= PENALTY_GAP * (j - k - 1) as i64;
+                }
+                if best[i][j].is_none_or(|current| score > current) {
+                    best[i][j] = Some(score);
+                    prev[i][j] = k;
+                }
+            }
+        }
+    }
+
+    let last = query.len() - 1;
+    let (mut j, score) = best[last]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, score)| score.map(|score| (j, score)))
+        .max_by_key(|(_, score)| *score)?;
+
+    let mut indices = vec![j; query.len()];
+    for i in (1..query.len()).rev() {
+        j = prev[i][j];
+        indices[i - 1] = j;
     }
+
+    Some(FuzzyMatch { score, indices })
 }
 
 /// Enum to represent the result of the search textfield
 enum SearchResult {
     /// User would still like to refine their search
-    ContinueSearch(String),
+    ContinueSearch,
+    /// User wants to highlight the result above the current one
+    SelectPrevious,
+    /// User wants to highlight the result below the current one
+    SelectNext,
+    /// User wants to yank a field of the highlighted result
+    Yank(AccountField),
+    /// User wants to yank the password of the highlighted result and then quit
+    YankAndQuit,
     /// User has found what they're looking for
-    SearchFinished(String),
+    SearchFinished,
     /// Exit the search
     Exit,
 }
 
-/// The searchbox textfield. A custom implementation of the terminal_drawing::textfield()
-fn search_textfield(prompt: String, prompt_len: u16, content: String) -> Result<SearchResult> {
+/// The searchbox textfield. A custom implementation of the terminal_drawing::textfield() that
+/// handles a single key press, editing the search term in place
+fn search_textfield(
+    prompt: String,
+    prompt_len: u16,
+    output: &mut String,
+    clipboard: &mut Clipboard,
+) -> Result<SearchResult> {
     execute!(stdout(), cursor::Show, cursor::SetCursorStyle::SteadyBlock)?;
 
-    let mut output = content;
-    let prompt = prompt;
-
     let cursor = output.len();
 
     execute!(
@@ -94,44 +474,236 @@ fn search_textfield(prompt: String, prompt_len: u16, content: String) -> Result<
         cursor::MoveToColumn(0),
         cursor::MoveRight(prompt_len + (cursor as u16))
     )?;
-    if let Event::Key(key) = read()? {
+    if let Event::Key(key) = clipboard.read_event()? {
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            return Ok(match key.code {
+                KeyCode::Char('p') => SearchResult::Yank(AccountField::Password),
+                KeyCode::Char('u') => SearchResult::Yank(AccountField::Username),
+                KeyCode::Char('e') => SearchResult::Yank(AccountField::Email),
+                KeyCode::Char('y') => SearchResult::YankAndQuit,
+                _ => SearchResult::ContinueSearch,
+            });
+        }
         match key.code {
             KeyCode::Char(char) => {
                 output.insert(cursor, char);
             }
             KeyCode::Backspace => {
-                if !output.is_empty() {
-                    output.remove(cursor - 1);
-                }
+                output.pop();
             }
-            KeyCode::Enter => return Ok(SearchResult::SearchFinished(output)),
+            KeyCode::Up => return Ok(SearchResult::SelectPrevious),
+            KeyCode::Down => return Ok(SearchResult::SelectNext),
+            KeyCode::Enter => return Ok(SearchResult::SearchFinished),
             KeyCode::Esc => return Ok(SearchResult::Exit),
             _ => (),
         }
     }
 
-    Ok(SearchResult::ContinueSearch(output))
+    Ok(SearchResult::ContinueSearch)
 }
 
-/// Draws the search results
-fn draw_search_results(accounts: &[Account]) -> Result<()> {
+/// Returns the given text with the characters at the given indices highlighted
+///
+/// # Arguments
+///
+/// * `text`    - The text to highlight
+/// * `indices` - The char indices of the characters to highlight
+fn highlight_matches(text: &str, indices: &[usize]) -> String {
+    text.chars()
+        .enumerate()
+        .map(|(i, c)| match indices.contains(&i) {
+            true => c.to_string().bright_yellow().bold().to_string(),
+            false => c.to_string(),
+        })
+        .collect()
+}
+
+/// Returns the line shown for an account in the search results, with the matched characters
+/// highlighted. If the match wasn't on the label, the field that matched is shown after it
+///
+/// # Arguments
+///
+/// * `account`       - The indexed account to format
+/// * `account_match` - How the account matched the search term
+fn format_search_result(account: &IndexedAccount, account_match: &AccountMatch) -> String {
+    let indices = account_match.matched.indices();
+    match account_match.field {
+        SearchField::Label => highlight_matches(account.label(), indices),
+        field => format!(
+            "{}  — {}: {}",
+            account.label(),
+            field.name(),
+            highlight_matches(account.field(field).unwrap_or_default(), indices)
+        ),
+    }
+}
+
+/// Returns the badge shown after an account whose password has expired or expires soon
+///
+/// # Arguments
+///
+/// * `account`   - The account to show the badge of
+/// * `now`       - The current time, in seconds since the Unix epoch
+/// * `warn_days` - How many days ahead expiring passwords get a badge
+fn expiry_badge(account: &Account, now: u64, warn_days: u64) -> Option<String> {
+    let expires = account.expires_at()?;
+    let badge = format!(" {} ", describe_expiry(expires, now));
+    match expires <= now {
+        true => Some(badge.bright_white().on_red().to_string()),
+        false if expires <= now.saturating_add(warn_days.saturating_mul(DAY)) => {
+            Some(badge.black().on_yellow().to_string())
+        }
+        false => None,
+    }
+}
+
+/// Draws the search results, or the syntax error in the query if it couldn't be parsed
+///
+/// # Arguments
+///
+/// * `index`     - The search index the results are positions in
+/// * `vault`     - The accounts in the vault, for their expiry badges
+/// * `accounts`  - The positions of the matching accounts and how they matched
+/// * `selected`  - Which of the results is highlighted
+/// * `warn_days` - How many days ahead expiring passwords get a badge
+fn draw_search_results(
+    index: &SearchIndex,
+    vault: &[Account],
+    accounts: &std::result::Result<Vec<(usize, AccountMatch)>, String>,
+    selected: usize,
+    warn_days: u64,
+) -> Result<()> {
     execute!(
         stdout(),
         cursor::MoveTo(0, 1),
         Clear(ClearType::FromCursorDown)
     )?;
 
-    let mut accounts_iter = accounts.iter();
-
-    if let Some(account) = accounts_iter.next() {
-        terminal_drawing::println(format!("> {}", account.label()))?;
-    }
+    let accounts = match accounts {
+        Ok(accounts) => accounts,
+        Err(error) => {
+            terminal_drawing::println(format!("{} {}", " ERROR ".bright_white().on_red(), error))?;
+            execute!(stdout(), cursor::MoveTo(0, 0))?;
+            return Ok(());
+        }
+    };
 
-    for account in accounts_iter {
-        terminal_drawing::println(format!("  {}", account.label()))?;
+    let now = time::now();
+    for (i, (position, account_match)) in accounts.iter().enumerate() {
+        let badge = expiry_badge(&vault[*position], now, warn_days)
+            .map(|badge| format!("  {}", badge))
+            .unwrap_or_default();
+        terminal_drawing::println(format!(
+            "{} {}{}",
+            if i == selected { '>' } else { ' ' },
+            format_search_result(index.account(*position), account_match),
+            badge
+        ))?;
     }
 
     execute!(stdout(), cursor::MoveTo(0, 0))?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{fuzzy_match, query, SearchField, SearchIndex};
+    use crate::account::Account;
+
+    #[test]
+    /// Tests that fuzzy_match() only matches the query as an in order subsequence
+    fn fuzzy_match_requires_order() {
+        assert!(fuzzy_match("gh", "github").is_some());
+        assert!(fuzzy_match("GH", "github").is_some());
+        assert!(fuzzy_match("hg", "github").is_none());
+        assert!(fuzzy_match("githubs", "github").is_none());
+    }
+
+    #[test]
+    /// Tests that fuzzy_match() picks the best alignment rather than the first one
+    fn fuzzy_match_finds_best_alignment() {
+        let matched = fuzzy_match("hub", "hotmail hub").unwrap();
+        assert_eq!(matched.indices(), &[8, 9, 10]);
+    }
+
+    #[test]
+    /// Tests that searching ranks prefix and word boundary matches first
+    fn search_ranks_results() {
+        let accounts = vec![
+            Account::builder().label("high score").build(),
+            Account::builder().label("Mail").build(),
+            Account::builder().label("github").build(),
+        ];
+
+        let index = SearchIndex::new(&accounts);
+
+        let labels: Vec<&str> = index
+            .search(&query::parse("gh").unwrap())
+            .iter()
+            .map(|(position, _)| index.account(*position).label())
+            .collect();
+
+        assert_eq!(labels, vec!["github", "high score"]);
+        assert_eq!(index.search(&query::parse("").unwrap()).len(), 3);
+    }
+
+    #[test]
+    /// Tests that each free text term is matched on its own, in any order and in any field
+    fn search_matches_terms_independently() {
+        let accounts = vec![
+            Account::builder().label("GitHub").username("alice").build(),
+            Account::builder().label("hub git").build(),
+            Account::builder().label("Steam").username("alice").build(),
+        ];
+
+        let index = SearchIndex::new(&accounts);
+        let labels = |input: &str| -> Vec<&str> {
+            index
+                .search(&query::parse(input).unwrap())
+                .iter()
+                .map(|(position, _)| index.account(*position).label())
+                .collect()
+        };
+
+        assert_eq!(labels("git hub"), vec!["GitHub", "hub git"]);
+        assert_eq!(labels("hub alice"), vec!["GitHub"]);
+        assert_eq!(labels("alice steam"), vec!["Steam"]);
+    }
+
+    #[test]
+    /// Tests that searching looks through every non-secret field, preferring label matches
+    fn search_looks_through_all_fields() {
+        let mut accounts = vec![
+            Account::builder()
+                .label("crates.io")
+                .email("sirdevsalot@gmail.com")
+                .build(),
+            Account::builder()
+                .label("github")
+                .username("sir_devs_a_lot")
+                .build(),
+            Account::builder().label("sir").build(),
+            Account::builder().label("forge").build(),
+        ];
+        accounts[3].set_notes(Some("recovery codes are with sir".to_string()));
+
+        let index = SearchIndex::new(&accounts);
+
+        let fields: Vec<(&str, SearchField)> = index
+            .search(&query::parse("sir").unwrap())
+            .iter()
+            .map(|(position, m)| (index.account(*position).label(), m.field))
+            .collect();
+
+        assert_eq!(
+            fields,
+            vec![
+                ("sir", SearchField::Label),
+                ("github", SearchField::Username),
+                ("crates.io", SearchField::Email),
+                ("forge", SearchField::Notes),
+            ]
+        );
+    }
+}
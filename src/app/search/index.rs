@@ -0,0 +1,321 @@
+//! This module contains the in-memory index the search page filters with, so that each keystroke
+//! only has to look at accounts that could possibly match instead of every account in the vault
+//!
+//! The index keeps its own copy of each account's searchable fields, along with posting lists
+//! mapping every lowercased character and trigram to the positions of the accounts containing
+//! it. Positions line up with the accounts vector the index was built from, so the index must be
+//! updated alongside every push, edit and removal on that vector
+
+use std::collections::HashMap;
+
+use crate::account::Account;
+
+use super::query::Query;
+use super::{match_account, AccountMatch, SearchField, SEARCH_FIELDS};
+
+/// The searchable fields of a single account, as stored in the index
+#[derive(Debug, Clone)]
+pub struct IndexedAccount {
+    /// The fields in the order of `SearchField::position()`
+    fields: [Option<String>; SEARCH_FIELDS.len()],
+    /// Lowercased copies of the fields, for case insensitive substring checks
+    lowercase_fields: [Option<String>; SEARCH_FIELDS.len()],
+}
+
+impl IndexedAccount {
+    /// Copies the searchable fields out of the given account
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The account to index
+    fn new(account: &Account) -> Self {
+        let fields = SEARCH_FIELDS.map(|(field, _)| field.value(account));
+        let lowercase_fields = fields
+            .clone()
+            .map(|field| field.map(|value| value.to_lowercase()));
+
+        Self {
+            fields,
+            lowercase_fields,
+        }
+    }
+
+    /// Returns the value of the given field, if the account has one
+    pub(super) fn field(&self, field: SearchField) -> Option<&str> {
+        self.fields[field.position()].as_deref()
+    }
+
+    /// Returns the lowercased value of the given field, if the account has one
+    pub(super) fn lowercase_field(&self, field: SearchField) -> Option<&str> {
+        self.lowercase_fields[field.position()].as_deref()
+    }
+
+    /// Returns the account's label
+    pub fn label(&self) -> &str {
+        self.field(SearchField::Label).unwrap_or_default()
+    }
+}
+
+/// An index over the searchable fields of every account in the vault
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    /// The indexed accounts, in the same order as the vault
+    accounts: Vec<IndexedAccount>,
+    /// Which accounts contain each lowercased character
+    chars: HashMap<char, Vec<usize>>,
+    /// Which accounts contain each lowercased trigram
+    trigrams: HashMap<[char; 3], Vec<usize>>,
+}
+
+impl SearchIndex {
+    /// Builds an index over the given accounts
+    ///
+    /// # Arguments
+    ///
+    /// * `accounts` - The accounts in the vault
+    pub fn new(accounts: &[Account]) -> Self {
+        let mut index = Self::default();
+        for account in accounts {
+            index.insert(account);
+        }
+        index
+    }
+
+    /// Returns the indexed account at the given position
+    pub fn account(&self, position: usize) -> &IndexedAccount {
+        &self.accounts[position]
+    }
+
+    /// Adds an account to the end of the index, to match it being pushed onto the vault
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The account that was added
+    pub fn insert(&mut self, account: &Account) {
+        self.accounts.push(IndexedAccount::new(account));
+        self.add_postings(self.accounts.len() - 1);
+    }
+
+    /// Reindexes the account at the given position after it has been edited
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The position of the account in the vault
+    /// * `account`  - The account with its edits
+    pub fn update(&mut self, position: usize, account: &Account) {
+        self.remove_postings(position);
+        self.accounts[position] = IndexedAccount::new(account);
+        self.add_postings(position);
+    }
+
+    /// Removes the account at the given position, shifting every later account down by one to
+    /// match it being removed from the vault
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The position of the account in the vault
+    pub fn remove(&mut self, position: usize) {
+        self.accounts.remove(position);
+
+        let postings = self.chars.values_mut().chain(self.trigrams.values_mut());
+        for posting in postings {
+            posting.retain(|&x| x != position);
+            for x in posting.iter_mut().filter(|x| **x > position) {
+                *x -= 1;
+            }
+        }
+
+        self.chars.retain(|_, posting| !posting.is_empty());
+        self.trigrams.retain(|_, posting| !posting.is_empty());
+    }
+
+    /// Finds the accounts matching the query, sorted from the best match to the worst. Accounts
+    /// with equal scores keep their vault order
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The parsed contents of the search box
+    ///
+    /// # Returns
+    ///
+    /// The positions of the matching accounts paired with the field they matched on
+    pub fn search(&self, query: &Query) -> Vec<(usize, AccountMatch)> {
+        let mut results: Vec<(usize, AccountMatch)> = self
+            .candidates(query)
+            .into_iter()
+            .filter(|&position| query.matches_filters(&self.accounts[position]))
+            .filter_map(|position| {
+                match_account(&self.accounts[position], query.terms()).map(|m| (position, m))
+            })
+            .collect();
+
+        results.sort_by_key(|(_, account_match)| std::cmp::Reverse(account_match.score));
+
+        results
+    }
+
+    /// Narrows the vault down to the accounts that contain every character of the free text and
+    /// every trigram of the query's positive filters. Every account that matches the query is a
+    /// candidate, but not every candidate matches
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The parsed contents of the search box
+    fn candidates(&self, query: &Query) -> Vec<usize> {
+        let mut postings: Vec<&[usize]> = vec![];
+
+        // The free text is fuzzy matched, so only its characters have to appear
+        for term in query.terms() {
+            for char in term.to_lowercase().chars() {
+                postings.push(self.chars.get(&char).map_or(&[], |x| x));
+            }
+        }
+
+        // Filter values have to appear as a whole, so all of their trigrams have to appear too
+        for value in query.required_substrings() {
+            let value: Vec<char> = value.to_lowercase().chars().collect();
+            if value.len() < 3 {
+                for char in value {
+                    postings.push(self.chars.get(&char).map_or(&[], |x| x));
+                }
+                continue;
+            }
+            for trigram in value.windows(3) {
+                let trigram = [trigram[0], trigram[1], trigram[2]];
+                postings.push(self.trigrams.get(&trigram).map_or(&[], |x| x));
+            }
+        }
+
+        postings.sort_by_key(|posting| posting.len());
+
+        let mut postings = postings.into_iter();
+        let mut candidates: Vec<usize> = match postings.next() {
+            Some(posting) => posting.to_vec(),
+            None => return (0..self.accounts.len()).collect(),
+        };
+
+        for posting in postings {
+            candidates.retain(|x| posting.binary_search(x).is_ok());
+        }
+
+        candidates
+    }
+
+    /// Adds the account at the given position to the posting lists of all its characters and
+    /// trigrams
+    fn add_postings(&mut self, position: usize) {
+        let (chars, trigrams) = self.keys(position);
+
+        for char in chars {
+            insert_sorted(self.chars.entry(char).or_default(), position);
+        }
+        for trigram in trigrams {
+            insert_sorted(self.trigrams.entry(trigram).or_default(), position);
+        }
+    }
+
+    /// Removes the account at the given position from the posting lists of all its characters
+    /// and trigrams, without shifting any other positions
+    fn remove_postings(&mut self, position: usize) {
+        let (chars, trigrams) = self.keys(position);
+
+        for char in chars {
+            if let Some(posting) = self.chars.get_mut(&char) {
+                posting.retain(|&x| x != position);
+            }
+        }
+        for trigram in trigrams {
+            if let Some(posting) = self.trigrams.get_mut(&trigram) {
+                posting.retain(|&x| x != position);
+            }
+        }
+    }
+
+    /// Returns every character and trigram in the lowercased searchable fields of the account at
+    /// the given position. Whole strings are lowercased rather than single characters, the same
+    /// as the query, since some characters lowercase to more than one
+    fn keys(&self, position: usize) -> (Vec<char>, Vec<[char; 3]>) {
+        let mut chars = vec![];
+        let mut trigrams = vec![];
+
+        for value in self.accounts[position].lowercase_fields.iter().flatten() {
+            let value: Vec<char> = value.chars().collect();
+            chars.extend(value.iter().copied());
+            trigrams.extend(value.windows(3).map(|x| [x[0], x[1], x[2]]));
+        }
+
+        (chars, trigrams)
+    }
+}
+
+/// Inserts the value into the sorted vector if it isn't already in it
+fn insert_sorted(posting: &mut Vec<usize>, value: usize) {
+    if let Err(insert_at) = posting.binary_search(&value) {
+        posting.insert(insert_at, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SearchIndex;
+    use crate::account::Account;
+    use crate::app::search::query;
+
+    /// Returns the labels of the accounts in the index matching the query, best match first
+    fn search_labels(index: &SearchIndex, query: &str) -> Vec<String> {
+        index
+            .search(&query::parse(query).unwrap())
+            .iter()
+            .map(|(position, _)| index.account(*position).label().to_string())
+            .collect()
+    }
+
+    #[test]
+    /// Tests that the index stays in sync with the vault as accounts are added, edited and
+    /// removed
+    fn index_updates_incrementally() {
+        let mut accounts = vec![
+            Account::builder().label("github").build(),
+            Account::builder().label("gitlab").build(),
+            Account::builder().label("Steam").build(),
+        ];
+        let mut index = SearchIndex::new(&accounts);
+
+        assert_eq!(search_labels(&index, "git"), vec!["github", "gitlab"]);
+
+        accounts.remove(0);
+        index.remove(0);
+        assert_eq!(search_labels(&index, "git"), vec!["gitlab"]);
+        assert_eq!(search_labels(&index, "label:steam"), vec!["Steam"]);
+
+        accounts[1].set_label("Steam Git".to_string());
+        index.update(1, &accounts[1]);
+        assert_eq!(
+            search_labels(&index, "label:git"),
+            vec!["gitlab", "Steam Git"]
+        );
+
+        let account = Account::builder().label("gitea").build();
+        index.insert(&account);
+        accounts.push(account);
+        assert_eq!(search_labels(&index, "gitea"), vec!["gitea"]);
+        assert_eq!(search_labels(&index, "").len(), accounts.len());
+    }
+
+    #[test]
+    /// Tests that characters lowercasing to more than one character are still found
+    fn index_lowercases_whole_strings() {
+        let accounts = vec![
+            Account::builder().label("İstanbul Kart").build(),
+            Account::builder().label("Straße").build(),
+        ];
+        let index = SearchIndex::new(&accounts);
+
+        assert_eq!(
+            search_labels(&index, "label:İstanbul"),
+            vec!["İstanbul Kart"]
+        );
+        assert_eq!(search_labels(&index, "İst"), vec!["İstanbul Kart"]);
+        assert_eq!(search_labels(&index, "label:STRAßE"), vec!["Straße"]);
+    }
+}
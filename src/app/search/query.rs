@@ -0,0 +1,493 @@
+//! This module contains the parser for the search box's query language
+//!
+//! # Syntax
+//!
+//! A query is a list of whitespace separated terms. Values containing spaces can be wrapped in
+//! double quotes
+//!
+//! * `word`         - Free text, each word fuzzy matched against every searchable field on its own
+//! * `field:value`  - Only accounts whose field contains the value
+//! * `-field:value` - Only accounts whose field does not contain the value
+//! * `tag:name`     - Only accounts with the tag, or without it when negated
+//! * `weak:yes`     - Only accounts whose password is below the audit's strength threshold
+//! * `age:>365d`    - Only accounts whose password is older, or with `<` newer, than the age
+//! * `sort:time`    - Lists the newest first by one of the account's times
+//! * `-sort:time`   - Lists the oldest first by one of the account's times
+//!
+//! The supported fields are `label`, `user` (or `username`), `email`, `url` and `note` (or
+//! `notes`). Ages are a number of days, or of weeks, months or years with a `w`, `m` or `y`. The
+//! times that can be sorted by are `created`, `modified`, `changed` (when the password changed)
+//! and `used`. Anything else, like `host:8080` or `-foo`, is searched for as free text
+
+use super::index::IndexedAccount;
+use super::SearchField;
+use crate::account::Account;
+use crate::time::days_between;
+
+/// Enum of the times accounts can be sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Created,
+    Modified,
+    PasswordChanged,
+    LastUsed,
+}
+
+impl SortKey {
+    /// Returns the time of the given account this key sorts by, if it's known
+    pub fn time(&self, account: &Account) -> Option<u64> {
+        match self {
+            SortKey::Created => account.created(),
+            SortKey::Modified => account.modified(),
+            SortKey::PasswordChanged => account.password_changed(),
+            SortKey::LastUsed => account.last_used(),
+        }
+    }
+}
+
+/// A filter on a single field of an account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    /// The field being filtered on
+    field: SearchField,
+    /// The lowercased text the field should contain
+    value: String,
+    /// Whether the field should not contain the value instead
+    negated: bool,
+}
+
+impl Filter {
+    /// Returns whether the given account passes the filter. Tags have to match a whole tag
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The indexed account to check
+    fn matches(&self, account: &IndexedAccount) -> bool {
+        let contains = account
+            .lowercase_field(self.field)
+            .is_some_and(|value| match self.field {
+                SearchField::Tags => value.split(", ").any(|tag| tag == self.value),
+                _ => value.contains(&self.value),
+            });
+
+        contains != self.negated
+    }
+}
+
+/// Enum of the checks on an account's password, which the index doesn't hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    /// The password is below the audit's strength threshold
+    Weak,
+    /// The password last changed more than the given number of days ago
+    OlderThan(u64),
+    /// The password last changed less than the given number of days ago
+    NewerThan(u64),
+}
+
+/// A check on an account's password that the account must pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasswordFilter {
+    /// What to check
+    check: Check,
+    /// Whether the account should fail the check instead
+    negated: bool,
+}
+
+impl PasswordFilter {
+    /// Returns whether the given account passes the filter. Accounts from before password changes
+    /// were recorded are aged from when they were created, and accounts whose age isn't known
+    /// never pass an age check
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The account to check
+    /// * `weak`    - Whether the account's password is weak
+    /// * `now`     - The current time, in seconds since the Unix epoch
+    fn matches(&self, account: &Account, weak: bool, now: u64) -> bool {
+        let age = || {
+            let changed = account.password_changed().or(account.created())?;
+            Some(days_between(changed, now))
+        };
+        let passes = match self.check {
+            Check::Weak => weak,
+            Check::OlderThan(days) => age().is_some_and(|age| age > days),
+            Check::NewerThan(days) => age().is_some_and(|age| age < days),
+        };
+
+        passes != self.negated
+    }
+}
+
+/// A parsed search query
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Query {
+    /// The free text terms, each fuzzy matched on its own
+    terms: Vec<String>,
+    /// The field filters an account must pass
+    filters: Vec<Filter>,
+    /// The password checks an account must pass
+    password_filters: Vec<PasswordFilter>,
+    /// The time to sort the results by, and whether the oldest come first
+    sort: Option<(SortKey, bool)>,
+}
+
+impl Query {
+    /// Returns the free text terms of the query to fuzzy match against
+    pub fn terms(&self) -> &[String] {
+        &self.terms
+    }
+
+    /// Returns whether the query is only free text, with no filters or sorting
+    pub fn is_plain_text(&self) -> bool {
+        self.filters.is_empty() && self.password_filters.is_empty() && self.sort.is_none()
+    }
+
+    /// Returns whether the query checks whether passwords are weak, which means working out the
+    /// strength of every password
+    pub fn checks_strength(&self) -> bool {
+        self.password_filters
+            .iter()
+            .any(|filter| filter.check == Check::Weak)
+    }
+
+    /// Returns the time to sort the results by and whether the oldest come first, if the results
+    /// are sorted by time instead of by how well they match
+    pub fn sort(&self) -> Option<(SortKey, bool)> {
+        self.sort
+    }
+
+    /// Returns the lowercased values that the filters require to appear in an account
+    pub fn required_substrings(&self) -> impl Iterator<Item = &str> {
+        self.filters
+            .iter()
+            .filter(|filter| !filter.negated)
+            .map(|filter| filter.value.as_str())
+    }
+
+    /// Returns whether the given account passes every field filter in the query. The free text
+    /// and password checks are not checked
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The indexed account to check
+    pub fn matches_filters(&self, account: &IndexedAccount) -> bool {
+        self.filters.iter().all(|filter| filter.matches(account))
+    }
+
+    /// Returns whether the given account passes every password check in the query
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The account to check
+    /// * `weak`    - Whether the account's password is weak
+    /// * `now`     - The current time, in seconds since the Unix epoch
+    pub fn matches_password(&self, account: &Account, weak: bool, now: u64) -> bool {
+        self.password_filters
+            .iter()
+            .all(|filter| filter.matches(account, weak, now))
+    }
+}
+
+/// Parses what the user has typed into the search box
+///
+/// # Arguments
+///
+/// * `input` - The raw search box contents
+///
+/// # Returns
+///
+/// Either the parsed query, or a message describing the syntax error
+pub fn parse(input: &str) -> Result<Query, String> {
+    let mut query = Query::default();
+
+    for token in tokenise(input) {
+        let (negated, term) = match token.strip_prefix('-') {
+            Some(rest) if !rest.is_empty() => (true, rest),
+            _ => (false, token.as_str()),
+        };
+        let Some((name, value)) = term.split_once(':') else {
+            query.terms.push(token);
+            continue;
+        };
+
+        let field = match name.to_lowercase().as_str() {
+            "sort" => {
+                let key = match value.to_lowercase().as_str() {
+                    "created" => SortKey::Created,
+                    "modified" => SortKey::Modified,
+                    "changed" => SortKey::PasswordChanged,
+                    "used" => SortKey::LastUsed,
+                    _ => return Err(format!("Can't sort by '{}'", value)),
+                };
+                query.sort = Some((key, negated));
+                continue;
+            }
+            "weak" => {
+                let weak = match value.to_lowercase().as_str() {
+                    "yes" | "y" | "true" => true,
+                    "no" | "n" | "false" => false,
+                    _ => return Err(format!("'weak:' takes yes or no, not '{}'", value)),
+                };
+                query.password_filters.push(PasswordFilter {
+                    check: Check::Weak,
+                    negated: negated == weak,
+                });
+                continue;
+            }
+            "age" => {
+                query.password_filters.push(PasswordFilter {
+                    check: parse_age(value)?,
+                    negated,
+                });
+                continue;
+            }
+            "label" => SearchField::Label,
+            "user" | "username" => SearchField::Username,
+            "email" => SearchField::Email,
+            "url" => SearchField::Url,
+            "tag" | "tags" => SearchField::Tags,
+            "note" | "notes" => SearchField::Notes,
+            // Labels like `host:8080` are searched for as they are
+            _ => {
+                query.terms.push(token);
+                continue;
+            }
+        };
+        if value.is_empty() {
+            return Err(format!("Missing a value after '{}:'", name));
+        }
+        query.filters.push(Filter {
+            field,
+            value: value.to_lowercase(),
+            negated,
+        });
+    }
+
+    Ok(query)
+}
+
+/// Parses the value of an `age:` filter, like `>365d`, `<2w` or `1y`. Ages without a comparison
+/// are a minimum
+///
+/// # Arguments
+///
+/// * `value` - What comes after `age:`
+///
+/// # Returns
+///
+/// Either the check on the password's age, or a message describing the syntax error
+fn parse_age(value: &str) -> Result<Check, String> {
+    let error = || {
+        format!(
+            "Can't read the age '{}', try something like age:>365d",
+            value
+        )
+    };
+
+    let (older, amount) = match value.strip_prefix('<') {
+        Some(amount) => (false, amount),
+        None => (true, value.strip_prefix('>').unwrap_or(value)),
+    };
+    let (amount, days_per_unit) = match amount.char_indices().last() {
+        Some((unit, 'd')) => (&amount[..unit], 1),
+        Some((unit, 'w')) => (&amount[..unit], 7),
+        Some((unit, 'm')) => (&amount[..unit], 30),
+        Some((unit, 'y')) => (&amount[..unit], 365),
+        _ => (amount, 1),
+    };
+    let days = amount
+        .parse::<u64>()
+        .map_err(|_| error())?
+        .saturating_mul(days_per_unit);
+
+    Ok(match older {
+        true => Check::OlderThan(days),
+        false => Check::NewerThan(days),
+    })
+}
+
+/// Splits the input on whitespace, keeping anything inside double quotes together. If a quote is
+/// left unclosed the quotes are kept as they are, so they can still be searched for
+///
+/// # Arguments
+///
+/// * `input` - The raw search box contents
+///
+/// # Returns
+///
+/// The tokens with their quotes removed
+fn tokenise(input: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut quoted = false;
+
+    for char in input.chars() {
+        match char {
+            '"' => {
+                in_quotes = !in_quotes;
+                quoted = true;
+            }
+            char if char.is_whitespace() && !in_quotes => {
+                if !current.is_empty() || quoted {
+                    tokens.push(std::mem::take(&mut current));
+                }
+                quoted = false;
+            }
+            char => current.push(char),
+        }
+    }
+
+    if in_quotes {
+        return input.split_whitespace().map(str::to_string).collect();
+    }
+
+    if !current.is_empty() || quoted {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, tokenise, SortKey};
+    use crate::account::Account;
+    use crate::app::search::index::SearchIndex;
+    use crate::time::DAY;
+
+    #[test]
+    /// Tests that tokenise() splits on whitespace and respects quotes
+    fn tokenise_handles_quotes() {
+        assert_eq!(
+            tokenise(r#"git user:"sir devs" -x"#),
+            vec!["git", "user:sir devs", "-x"]
+        );
+        assert_eq!(tokenise(r#"user:"sir devs"#), vec![r#"user:"sir"#, "devs"]);
+    }
+
+    #[test]
+    /// Tests that parse() separates free text from filters and rejects bad filters
+    fn parse_works() {
+        let query = parse("git hub email:@corp.com").unwrap();
+        assert_eq!(query.terms(), ["git", "hub"]);
+        assert!(!query.is_plain_text());
+
+        assert!(parse("git hub").unwrap().is_plain_text());
+        assert!(parse("user:").is_err());
+        assert!(parse("weak:maybe").is_err());
+        assert!(parse("age:old").is_err());
+    }
+
+    #[test]
+    /// Tests that terms that aren't filters are searched for as they are
+    fn parse_falls_back_to_text() {
+        let query = parse("host:8080 -foo -").unwrap();
+        assert_eq!(query.terms(), ["host:8080", "-foo", "-"]);
+        assert!(query.is_plain_text());
+    }
+
+    #[test]
+    /// Tests that sort terms pick the time and order, and reject unknown times
+    fn parse_sort_works() {
+        assert_eq!(parse("git").unwrap().sort(), None);
+        assert_eq!(
+            parse("git sort:used").unwrap().sort(),
+            Some((SortKey::LastUsed, false))
+        );
+        assert_eq!(
+            parse("-sort:changed").unwrap().sort(),
+            Some((SortKey::PasswordChanged, true))
+        );
+        assert!(!parse("sort:created").unwrap().is_plain_text());
+        assert!(parse("sort:colour").is_err());
+    }
+
+    #[test]
+    /// Tests that filters and negations are applied to accounts
+    fn matches_filters_works() {
+        let mut accounts = [
+            Account::builder()
+                .label("Jira")
+                .username("alice")
+                .email("alice@corp.com")
+                .build(),
+            Account::builder()
+                .label("Steam")
+                .username("alice")
+                .email("alice@gmail.com")
+                .build(),
+        ];
+        accounts[1].set_tags(vec!["Archived".to_string()]);
+        let index = SearchIndex::new(&accounts);
+        let (work, personal) = (index.account(0), index.account(1));
+
+        let query = parse("user:ALICE email:@corp.com").unwrap();
+        assert!(query.matches_filters(work));
+        assert!(!query.matches_filters(personal));
+
+        let query = parse("-email:@corp.com").unwrap();
+        assert!(!query.matches_filters(work));
+        assert!(query.matches_filters(personal));
+
+        let query = parse("-tag:archived").unwrap();
+        assert!(query.matches_filters(work));
+        assert!(!query.matches_filters(personal));
+    }
+
+    #[test]
+    /// Tests that tags have to match a whole tag
+    fn tag_filters_match_whole_tags() {
+        let mut account = Account::builder().label("Jira").build();
+        account.set_tags(vec!["work".to_string(), "shared".to_string()]);
+        let index = SearchIndex::new(&[account]);
+
+        assert!(parse("tag:WORK").unwrap().matches_filters(index.account(0)));
+        assert!(parse("tags:shared")
+            .unwrap()
+            .matches_filters(index.account(0)));
+        assert!(!parse("tag:wor").unwrap().matches_filters(index.account(0)));
+        assert!(!parse("-tag:work")
+            .unwrap()
+            .matches_filters(index.account(0)));
+    }
+
+    #[test]
+    /// Tests that weak and age checks are applied to passwords
+    fn matches_password_works() {
+        let account = Account::builder().label("Jira").created(0).build();
+        let now = 400 * DAY;
+
+        assert!(parse("weak:yes").unwrap().checks_strength());
+        assert!(!parse("age:>1y").unwrap().checks_strength());
+
+        assert!(parse("weak:yes")
+            .unwrap()
+            .matches_password(&account, true, now));
+        assert!(!parse("weak:no")
+            .unwrap()
+            .matches_password(&account, true, now));
+        assert!(parse("-weak:yes")
+            .unwrap()
+            .matches_password(&account, false, now));
+
+        assert!(parse("age:>365d")
+            .unwrap()
+            .matches_password(&account, false, now));
+        assert!(parse("age:1y")
+            .unwrap()
+            .matches_password(&account, false, now));
+        assert!(!parse("age:<52w")
+            .unwrap()
+            .matches_password(&account, false, now));
+        assert!(!parse("-age:>365d")
+            .unwrap()
+            .matches_password(&account, false, now));
+
+        let unknown = Account::builder().label("Steam").build();
+        assert!(!parse("age:>1d")
+            .unwrap()
+            .matches_password(&unknown, false, now));
+    }
+}
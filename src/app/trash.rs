@@ -0,0 +1,113 @@
+//! This module contains the trash page, which lists deleted accounts so they can be restored or
+//! purged for good
+
+use colored::Colorize;
+use crossterm::{
+    cursor,
+    event::{Event, KeyCode},
+    execute,
+    terminal::{Clear, ClearType},
+    Result,
+};
+use std::io::stdout;
+
+use crate::account::Account;
+use crate::clipboard::Clipboard;
+use crate::terminal_drawing::{box_label, get_confirmation, println};
+use crate::time::{self, days_between, format_date};
+use crate::trash::purged_on;
+
+/// Enum of what the user chose on the trash page
+pub enum TrashAction {
+    /// Move the account at the given position in the trash back into the vault
+    Restore(usize),
+    /// Permanently delete the account at the given position in the trash
+    Purge(usize),
+    /// Go back to the home page
+    Exit,
+}
+
+/// Entry point for the trash page. Lets the user pick a trashed account to restore or purge
+///
+/// # Arguments
+///
+/// * `trash`     - The accounts in the trash
+/// * `retention` - How many days accounts are kept in the trash, or 0 to keep them forever
+/// * `clipboard` - The clipboard, to show the status of
+///
+/// # Returns
+///
+/// Either what the user chose, or an IO error
+pub fn show_trash(
+    trash: &[Account],
+    retention: u64,
+    clipboard: &mut Clipboard,
+) -> Result<TrashAction> {
+    let mut selected = 0;
+
+    loop {
+        draw_trash(trash, retention, selected)?;
+        if let Event::Key(key) = clipboard.read_event()? {
+            match key.code {
+                KeyCode::Char('j') | KeyCode::Down if selected + 1 < trash.len() => selected += 1,
+                KeyCode::Char('k') | KeyCode::Up => selected = selected.saturating_sub(1),
+                KeyCode::Char('r') | KeyCode::Enter if selected < trash.len() => {
+                    return Ok(TrashAction::Restore(selected))
+                }
+                KeyCode::Char('D') if selected < trash.len() => {
+                    println("")?;
+                    println("Permanently delete this account? This can't be undone [y/N]")?;
+                    if get_confirmation()? {
+                        return Ok(TrashAction::Purge(selected));
+                    }
+                }
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(TrashAction::Exit),
+                _ => (),
+            }
+        }
+    }
+}
+
+/// Draws the trash page to the terminal
+///
+/// # Arguments
+///
+/// * `trash`     - The accounts in the trash
+/// * `retention` - How many days accounts are kept in the trash, or 0 to keep them forever
+/// * `selected`  - The position of the selected account
+fn draw_trash(trash: &[Account], retention: u64, selected: usize) -> Result<()> {
+    execute!(
+        stdout(),
+        cursor::MoveTo(0, 0),
+        Clear(ClearType::FromCursorDown),
+        cursor::Hide
+    )?;
+
+    println(box_label("Trash"))?;
+    if trash.is_empty() {
+        println("The trash is empty")?;
+    }
+
+    let now = time::now();
+    for (position, account) in trash.iter().enumerate() {
+        let deleted = account
+            .trashed()
+            .map(|x| format!("deleted {}", format_date(x)))
+            .unwrap_or_default();
+        let purged = match purged_on(account, retention) {
+            Some(purged) => format!(" · purged in {} days", days_between(now, purged)),
+            None => String::new(),
+        };
+        println(format!(
+            "{} {}  {}",
+            if position == selected { '>' } else { ' ' },
+            account.label(),
+            format!("{}{}", deleted, purged).dimmed()
+        ))?;
+    }
+
+    println("")?;
+    println("j/k move  r restore  D delete forever  ESC back")?;
+
+    Ok(())
+}
@@ -1,29 +1,46 @@
 //! This module contains everything thing to do with viewing an account in the application
 
+use colored::Colorize;
 use crossterm::{
     cursor,
-    event::{read, Event, KeyCode},
+    event::{Event, KeyCode},
     execute,
     terminal::{Clear, ClearType},
     Result,
 };
-use rand::Rng;
 use std::io::stdout;
 
-use arboard::Clipboard;
+use crate::account::{describe_expiry, Account, Expiry};
+use crate::breach::BreachList;
+use crate::clipboard::Clipboard;
+use crate::config::Config;
+use crate::generator::DerivationOptions;
+use crate::policy::Policy;
+use crate::rotation::{rotate, RotationScript, ScriptRun};
+use crate::strength::estimate;
+use crate::terminal_drawing::{
+    box_label, get_confirmation, print, println, textfield, textfield_with_hint,
+};
+use crate::time::{self, format_date};
 
-use crate::account::Account;
-use crate::terminal_drawing::{box_label, get_confirmation, print, println, textfield};
+use super::derive::{derive, DeriveAction};
+use super::generate::generate;
+use super::history::{show_history, HistoryAction};
+use super::notification::show_notification;
+use super::strength_hint;
 
 use AccountField::*;
 
 /// Enum that contains all the fields in the Account struct
 #[derive(Clone, Copy)]
-enum AccountField {
+pub(super) enum AccountField {
     Label,
     Username,
     Email,
     Password,
+    Url,
+    Tags,
+    Notes,
 }
 
 impl AccountField {
@@ -33,152 +50,510 @@ impl AccountField {
             Label => Username,
             Username => Email,
             Email => Password,
-            Password => Label,
+            Password => Url,
+            Url => Tags,
+            Tags => Notes,
+            Notes => Label,
         }
     }
 
     /// Returns what the previous field is
     fn prev(&self) -> AccountField {
         match self {
-            Label => Password,
+            Label => Notes,
             Username => Label,
             Email => Username,
             Password => Email,
+            Url => Password,
+            Tags => Url,
+            Notes => Tags,
         }
     }
 }
 
+/// Enum of what the user chose to do with the account on the view page
+pub enum ViewAction {
+    /// Keep the account with the changes made
+    Save(Account),
+    /// Delete the account
+    Delete,
+    /// Keep the account with the changes made, then list every account using its password
+    FindSharing(Account, String),
+    /// Keep the account with the changes made and save the vault straight away, as its rotation
+    /// script has changed the password on the service, then view it again
+    Rotated(Account),
+}
+
 /// Entry point for the view page of the application. Allows the user to yank details like a
 /// password into their system's clipboard, edit details to do with that account, and delete it.
 ///
 /// # Arguments
 ///
-/// * `account` - The account to view
+/// * `account`   - The account to view
+/// * `master`    - The master password derived passwords come from
+/// * `config`    - The user's config
+/// * `clipboard` - The clipboard to yank fields into
 ///
 /// # Returns
 ///
-/// Either the account with the changes made, None if the user deletes it, or an IO error
-pub fn view(account: Account) -> Result<Option<Account>> {
+/// Either what to do with the account, or an IO error
+pub fn view(
+    account: Account,
+    master: &str,
+    config: &mut Config,
+    clipboard: &mut Clipboard,
+) -> Result<ViewAction> {
     let mut account = account;
     let mut current_field = Label;
+    // Deriving is slow on purpose, so the passwords are only worked out again after changes
+    let mut password = account.resolve_password(master);
+    let mut pending = account.pending_password(master);
     loop {
-        draw_view(&account, current_field)?;
-        if let Event::Key(key) = read()? {
+        draw_view(&account, &password, &pending, current_field)?;
+        if let Event::Key(key) = clipboard.read_event()? {
             match key.code {
                 KeyCode::Char('j') => current_field = current_field.next(),
                 KeyCode::Char('k') => current_field = current_field.prev(),
-                KeyCode::Char('e') => account = edit(account, current_field)?,
-                KeyCode::Char('y') => yank_current_field(&account, current_field)?,
-                KeyCode::Char('D') => {
-                    if confirm_delete_list()? {
-                        return Ok(None);
-                    }
+                KeyCode::Char('e') => match (current_field, account.derivation()) {
+                    (Password, Some(_)) => show_notification(
+                        "This password is derived, press d to change what it's derived from",
+                    )?,
+                    _ => account = edit(account, current_field)?,
+                },
+                KeyCode::Char('P') => account = edit_policy(account)?,
+                KeyCode::Char('S') => account = edit_rotation_script(account)?,
+                KeyCode::Char('E') => account = edit_expiry(account)?,
+                KeyCode::Char('T') => account = edit_temporary(account)?,
+                KeyCode::Char('R') => match account.rotation_script() {
+                    Some(_) => match rotate(&mut account, master, time::now()) {
+                        Ok(()) => {
+                            if let Ok(new_password) = account.resolve_password(master) {
+                                warn_if_breached(&new_password, config)?;
+                            }
+                            return Ok(ViewAction::Rotated(account));
+                        }
+                        Err(error) => show_notification(error)?,
+                    },
+                    None => show_notification("Press S to set a rotation script first")?,
+                },
+                KeyCode::Char('y') => {
+                    yank_current_field(&mut account, current_field, master, clipboard)?
+                }
+                KeyCode::Char('D') if confirm_delete_list(&account)? => {
+                    return Ok(ViewAction::Delete)
                 }
-                KeyCode::Char('G') => {
-                    if confirm_random_password()? {
-                        account.set_password(generate_random_password());
+                KeyCode::Char('u') => match &password {
+                    Ok(shared) if !shared.is_empty() => {
+                        return Ok(ViewAction::FindSharing(account, shared.clone()))
+                    }
+                    _ => show_notification("This account doesn't have a password to look up")?,
+                },
+                // The new password is only pending until it's been changed on the site too
+                KeyCode::Char('G') => match account.derivation() {
+                    // Derived passwords rotate to the next counter, unless the next one breaks
+                    // the site's policy
+                    Some(_) => {
+                        let mut rotated = account.clone();
+                        rotated.start_rotation(String::new());
+                        let broken = match (account.policy(), rotated.pending_password(master)) {
+                            (Some(policy), Some(Ok(pending))) => policy.check(&pending).err(),
+                            _ => None,
+                        };
+                        match broken {
+                            Some(error) => show_notification(format!(
+                                "The site's policy says the next password {}, press d to change \
+                                 how it's derived",
+                                error
+                            ))?,
+                            None => account = rotated,
+                        }
+                    }
+                    None => {
+                        let policy = account.policy();
+                        if let Some(password) = generate(config, policy.as_ref(), clipboard)? {
+                            account.start_rotation(password);
+                        }
+                    }
+                },
+                KeyCode::Char('Y') => match &pending {
+                    Some(Ok(pending)) => {
+                        clipboard.yank(pending.clone())?;
+                        account.record_use();
+                    }
+                    Some(Err(error)) => show_notification(error)?,
+                    None => show_notification("There's no pending password, press G to start one")?,
+                },
+                KeyCode::Char('c') if account.is_rotating() => account.confirm_rotation(master),
+                KeyCode::Char('X') if account.is_rotating() => account.cancel_rotation(),
+                KeyCode::Char('d') => account = edit_derivation(account, master, clipboard)?,
+                KeyCode::Char('H') => {
+                    if let HistoryAction::Restore(position) = show_history(&account, clipboard)? {
+                        account.restore_password(position);
                     }
                 }
                 KeyCode::Esc | KeyCode::Char('q') => break,
                 _ => (),
             }
+            if let KeyCode::Char('e' | 'G' | 'd' | 'c' | 'X' | 'R' | 'H') = key.code {
+                let old_password =
+                    std::mem::replace(&mut password, account.resolve_password(master));
+                let old_pending = std::mem::replace(&mut pending, account.pending_password(master));
+
+                // Confirming a rotation only makes the pending password current, which was
+                // already checked when it was generated
+                let checks = [
+                    (
+                        password.as_ref().ok(),
+                        password != old_password && key.code != KeyCode::Char('c'),
+                    ),
+                    (
+                        pending.as_ref().and_then(|x| x.as_ref().ok()),
+                        pending != old_pending,
+                    ),
+                ];
+                for (new_password, changed) in checks {
+                    if let (Some(new_password), true) = (new_password, changed) {
+                        warn_if_breached(new_password, config)?;
+                    }
+                }
+            }
         }
     }
-    Ok(Some(account))
+    Ok(ViewAction::Save(account))
 }
 
-/// Prompts the user to confirm whether they'd actually like to delete the account being viewed
+/// Allows the user to edit the account's policy with a textfield. The textfield starts with the
+/// policy of the account's site if it's a well-known one, and the name of a well-known site can
+/// be entered instead of rules
+///
+/// # Arguments
+///
+/// * `account` - The account to change
 ///
 /// # Returns
 ///
-/// `true` if the user presses y or Y, any other key results in `false`. Otherwise an IO error
-fn confirm_delete_list() -> Result<bool> {
-    execute!(stdout(), cursor::MoveTo(0, 5))?;
-    println("Are you sure you want to delete this account? [y/N]")?;
-    get_confirmation()
+/// The account with the user's changes, or an IO error
+fn edit_policy(account: Account) -> Result<Account> {
+    let mut account = account;
+    let site = match account.derivation() {
+        Some(derivation) => derivation.site().to_string(),
+        None => account.label(),
+    };
+    let content = account
+        .policy()
+        .or_else(|| Policy::known(&site))
+        .map(|policy| policy.to_rules())
+        .unwrap_or_default();
+
+    execute!(stdout(), cursor::MoveTo(0, policy_line(&account)))?;
+    let new_value = textfield(format!("{} ", box_label("Policy")), 9, content, false)?;
+
+    if let Some(new_value) = new_value.map(|x| x.trim().to_string()) {
+        let policy = match new_value.contains('=') {
+            true => Policy::from_rules(&new_value),
+            false => Policy::known(&new_value),
+        };
+
+        match (new_value.is_empty(), policy) {
+            (true, _) => account.set_policy(None),
+            (false, Some(policy)) => account.set_policy(Some(policy)),
+            (false, None) => {
+                show_notification(format!("Couldn't read the policy \"{}\"", new_value))?
+            }
+        }
+    }
+
+    Ok(account)
 }
 
-/// Prompts the user to confirm whether they'd actually like to generate a random password for the
-/// account
+/// Allows the user to set the script that rotates the account's password, then how many days
+/// apart it's run. Clearing the command removes the script
+///
+/// # Arguments
+///
+/// * `account` - The account to change
 ///
 /// # Returns
 ///
-/// `true` if the user presses y or Y, any other key results in `false`. Otherwise an IO error
-fn confirm_random_password() -> Result<bool> {
-    execute!(stdout(), cursor::MoveTo(0, 5))?;
-    println("Are you sure you want to generate a random password? [y/N]")?;
-    get_confirmation()
+/// The account with the user's changes, or an IO error
+fn edit_rotation_script(account: Account) -> Result<Account> {
+    let mut account = account;
+    let script = account.rotation_script();
+
+    execute!(stdout(), cursor::MoveTo(0, script_line(&account)))?;
+    let command = textfield(
+        format!("{} ", box_label("Rotation script")),
+        18,
+        script
+            .as_ref()
+            .map(|x| x.command().to_string())
+            .unwrap_or_default(),
+        false,
+    )?;
+    let Some(command) = command.map(|x| x.trim().to_string()) else {
+        return Ok(account);
+    };
+    if command.is_empty() {
+        account.set_rotation_script(None);
+        return Ok(account);
+    }
+
+    execute!(
+        stdout(),
+        cursor::MoveTo(0, script_line(&account)),
+        Clear(ClearType::CurrentLine)
+    )?;
+    let interval = textfield(
+        format!("{} ", box_label("Rotate every (days)")),
+        22,
+        script
+            .map(|x| x.interval())
+            .unwrap_or(DEFAULT_ROTATION_INTERVAL)
+            .to_string(),
+        false,
+    )?;
+    match interval.map(|x| x.trim().parse::<u64>()) {
+        Some(Ok(interval)) if interval > 0 => {
+            account.set_rotation_script(Some(RotationScript::new(command, interval)))
+        }
+        Some(_) => show_notification("The interval must be a whole number of days")?,
+        None => (),
+    }
+
+    Ok(account)
 }
 
-const AVERAGE_PASSWORD_LENGTH: usize = 20;
-const ASCII_MIN: u8 = 33;
-const ASCII_MAX: u8 = 126;
+/// Allows the user to set when the account's password expires, as a date or a number of days
+/// after it changes. Clearing the textfield stops the password expiring
+///
+/// # Arguments
+///
+/// * `account` - The account to change
+///
+/// # Returns
+///
+/// The account with the user's changes, or an IO error
+fn edit_expiry(account: Account) -> Result<Account> {
+    let mut account = account;
+    let content = account.expiry().map(|x| x.to_string()).unwrap_or_default();
+
+    execute!(stdout(), cursor::MoveTo(0, expiry_line(&account)))?;
+    let new_value = textfield(format!("{} ", box_label("Expires")), 10, content, false)?;
 
-/// Generates a random password for the account
+    if let Some(new_value) = new_value.map(|x| x.trim().to_string()) {
+        match (new_value.is_empty(), Expiry::parse(&new_value)) {
+            (true, _) => account.set_expiry(None),
+            (false, Some(expiry)) => account.set_expiry(Some(expiry)),
+            (false, None) => show_notification(
+                "Enter a date like 2024-02-29, or how many days the password lasts like 90",
+            )?,
+        }
+    }
+
+    Ok(account)
+}
+
+/// Allows the user to make the account temporary, so it's moved to the trash once its date has
+/// passed. The date can be typed as a date or a number of days from now, and clearing the
+/// textfield keeps the account
+///
+/// # Arguments
+///
+/// * `account` - The account to change
 ///
 /// # Returns
 ///
-/// A random password as a String
-pub fn generate_random_password() -> String {
-    let mut password = String::new();
-    let password_length = rand::thread_rng()
-        .gen_range(AVERAGE_PASSWORD_LENGTH.saturating_sub(4)..=AVERAGE_PASSWORD_LENGTH + 4);
+/// The account with the user's changes, or an IO error
+fn edit_temporary(account: Account) -> Result<Account> {
+    let mut account = account;
+    let content = account.deletes_on().map(format_date).unwrap_or_default();
 
-    for _ in 0..password_length {
-        let mut char = rand::thread_rng().gen_range(ASCII_MIN..=ASCII_MAX) as char;
-        while !char.is_alphanumeric() {
-            char = rand::thread_rng().gen_range(ASCII_MIN..=ASCII_MAX) as char;
+    execute!(stdout(), cursor::MoveTo(0, temporary_line(&account)))?;
+    let new_value = textfield(format!("{} ", box_label("Delete on")), 12, content, false)?;
+
+    if let Some(new_value) = new_value.map(|x| x.trim().to_string()) {
+        match (new_value.is_empty(), Expiry::parse(&new_value)) {
+            (true, _) => account.set_deletes_on(None),
+            (false, Some(Expiry::On(time))) => account.set_deletes_on(Some(time)),
+            (false, Some(Expiry::Every(days))) => account.set_deletes_on(Some(
+                time::now().saturating_add(days.saturating_mul(time::DAY)),
+            )),
+            (false, None) => show_notification(
+                "Enter a date like 2024-02-29, or how many days the account lasts like 30",
+            )?,
         }
-        password.push(char);
     }
 
-    password
+    Ok(account)
 }
 
-/// Yanks the given field in the Account into the clipboard. For some reason this only works
-/// while this function doesn't return, so at the end of a function there is a `read()` call, to
-/// prevent the function from returning until a key is pressed.
+/// Shows a warning if the password appears in the configured list of breached passwords
 ///
 /// # Arguments
 ///
-/// * `account` - The account to yank the field from
-/// * `field`   - The field to yank
+/// * `password` - The password that was just set
+/// * `config`   - The user's configuration, with the path of the breach list
 ///
 /// # Returns
 ///
 /// Can return an IO error
-fn yank_current_field(account: &Account, field: AccountField) -> Result<()> {
-    let mut clipboard = Clipboard::new().expect("Couldn't access the clipboard");
-    // Yanking the given field to the clipboard
-    match field {
-        Label => return Ok(()),
-        Username => {
-            if let Some(username) = account.username() {
-                clipboard
-                    .set_text(username)
-                    .expect("Couldn't access the clipboard")
-            }
+fn warn_if_breached(password: &str, config: &Config) -> Result<()> {
+    let Some(breach_file) = config.breach_file() else {
+        return Ok(());
+    };
+    if password.is_empty() {
+        return Ok(());
+    }
+
+    match BreachList::open(breach_file).and_then(|list| list.occurrences(password)) {
+        Ok(Some(count)) => show_notification(format!(
+            "Warning: this password has been seen {} times in data breaches",
+            count
+        )),
+        Ok(None) => Ok(()),
+        Err(error) => show_notification(error),
+    }
+}
+
+/// How many days apart rotation scripts are run unless the user picks something else
+const DEFAULT_ROTATION_INTERVAL: u64 = 90;
+
+/// The line the pending password of a rotation is drawn on
+const PENDING_LINE: u16 = 7;
+
+/// Returns the line the account's derivation is drawn on, which is below the pending password if
+/// the account is being rotated
+fn derivation_line(account: &Account) -> u16 {
+    PENDING_LINE + u16::from(account.is_rotating())
+}
+
+/// Returns the line the account's policy is drawn on, which is below the derivation line if the
+/// account has one
+fn policy_line(account: &Account) -> u16 {
+    derivation_line(account) + u16::from(account.derivation().is_some())
+}
+
+/// Returns the line the account's rotation script is drawn on, which is below the policy line if
+/// the account has one
+fn script_line(account: &Account) -> u16 {
+    policy_line(account) + u16::from(account.policy().is_some())
+}
+
+/// Returns the line the account's expiry is drawn on, which is below the rotation script line if
+/// the account has one
+fn expiry_line(account: &Account) -> u16 {
+    script_line(account) + u16::from(account.rotation_script().is_some())
+}
+
+/// Returns the line the time a temporary account deletes itself is drawn on, which is below the
+/// expiry line if the account has one
+fn temporary_line(account: &Account) -> u16 {
+    expiry_line(account) + u16::from(account.expiry().is_some())
+}
+
+/// Returns the line the account's timestamps are drawn on, below every other row
+fn times_line(account: &Account) -> u16 {
+    temporary_line(account) + u16::from(account.deletes_on().is_some())
+}
+
+/// Returns the first line below every row of the account
+fn end_line(account: &Account) -> u16 {
+    times_line(account) + 1
+}
+
+/// Opens the derivation dialog for the account, starting with the site and login taken from the
+/// account if its password isn't derived yet
+///
+/// # Arguments
+///
+/// * `account`   - The account to change
+/// * `master`    - The master password derived passwords come from
+/// * `clipboard` - The clipboard, to show the status of
+///
+/// # Returns
+///
+/// The account with the user's changes, or an IO error
+fn edit_derivation(account: Account, master: &str, clipboard: &mut Clipboard) -> Result<Account> {
+    let mut account = account;
+    let policy = account.policy();
+    let options = account.derivation().unwrap_or_else(|| {
+        let login = account.username().or(account.email()).unwrap_or_default();
+        let mut options = DerivationOptions::new(account.label().to_lowercase(), login);
+        if let Some(policy) = &policy {
+            policy.constrain_derivation(&mut options);
         }
-        Email => {
-            if let Some(email) = account.email() {
-                clipboard
-                    .set_text(email)
-                    .expect("Couldn't access the clipboard")
+        options
+    });
+
+    match derive(options, policy.as_ref(), master, clipboard)? {
+        DeriveAction::Derive(options) => account.set_derivation(Some(options)),
+        DeriveAction::Stop => {
+            if let Err(error) = account.store_derived_password(master) {
+                show_notification(error)?;
             }
         }
-        Password => clipboard
-            .set_text(account.password())
-            .expect("Couldn't access the clipboard"),
+        DeriveAction::Cancel => (),
     }
 
-    // Pausing execution so that the field stays in the clipboard
-    execute!(stdout(), cursor::MoveTo(0, 5))?;
+    Ok(account)
+}
 
-    println("Yanked! Press 'y' to wipe the clipboard")?;
+/// Prompts the user to confirm whether they'd actually like to move the account being viewed to
+/// the trash
+///
+/// # Arguments
+///
+/// * `account` - The account being viewed
+///
+/// # Returns
+///
+/// `true` if the user presses y or Y, any other key results in `false`. Otherwise an IO error
+fn confirm_delete_list(account: &Account) -> Result<bool> {
+    execute!(stdout(), cursor::MoveTo(0, end_line(account) + 1))?;
+    println("Move this account to the trash? [y/N]")?;
+    get_confirmation()
+}
 
-    while !get_confirmation()? {} // While loop runs until the user press y
+/// Yanks the given field in the Account into the clipboard, which clears itself after the
+/// configured timeout, and records that the Account was used
+///
+/// # Arguments
+///
+/// * `account`   - The account to yank the field from
+/// * `field`     - The field to yank
+/// * `master`    - The master password derived passwords come from
+/// * `clipboard` - The clipboard to yank into
+///
+/// # Returns
+///
+/// Can return an IO error
+pub(super) fn yank_current_field(
+    account: &mut Account,
+    field: AccountField,
+    master: &str,
+    clipboard: &mut Clipboard,
+) -> Result<()> {
+    let text = match field {
+        Label => None,
+        Username => account.username(),
+        Email => account.email(),
+        Password => match account.resolve_password(master) {
+            Ok(password) => Some(password),
+            Err(error) => return show_notification(error),
+        },
+        Url => account.url(),
+        Tags => Some(account.tags().join(", ")).filter(|tags| !tags.is_empty()),
+        Notes => account.notes(),
+    };
 
-    Ok(())
+    match text {
+        Some(text) => {
+            clipboard.yank(text)?;
+            account.record_use();
+            Ok(())
+        }
+        None => Ok(()),
+    }
 }
 
 /// Allows the user to edit the given field with a textfield
@@ -213,13 +588,31 @@ fn edit(account: Account, current_field: AccountField) -> Result<Account> {
             execute!(stdout(), cursor::MoveTo(0, 3))?;
             ("Password", account.password())
         }
+        Url => {
+            execute!(stdout(), cursor::MoveTo(0, 4))?;
+            ("URL", account.url().unwrap_or_default())
+        }
+        Tags => {
+            execute!(stdout(), cursor::MoveTo(0, 5))?;
+            ("Tags", account.tags().join(", "))
+        }
+        Notes => {
+            execute!(stdout(), cursor::MoveTo(0, 6))?;
+            ("Notes", account.notes().unwrap_or_default())
+        }
     };
 
-    let new_value = textfield(
+    // Only the password gets a strength meter while it's typed
+    let hint = match current_field {
+        Password => strength_hint,
+        _ => |_: &str| String::new(),
+    };
+    let new_value = textfield_with_hint(
         format!("{} ", box_label(label)),
         (label.len() + 3) as u16,
         content,
         false,
+        hint,
     )?;
 
     // Making the edit if an edit was made
@@ -234,20 +627,57 @@ fn edit(account: Account, current_field: AccountField) -> Result<Account> {
                 true => account.set_email(Some(new_value)),
                 false => account.set_email(None),
             },
-            Password => account.set_password(new_value),
+            Password => {
+                if let Some(Err(error)) = account.policy().map(|x| x.check(&new_value)) {
+                    show_notification(format!(
+                        "Warning: the site's policy says the password {}",
+                        error
+                    ))?;
+                }
+                account.set_password(new_value);
+            }
+            Url => match !new_value.is_empty() {
+                true => account.set_url(Some(new_value)),
+                false => account.set_url(None),
+            },
+            Tags => account.set_tags(new_value.split(',').map(str::to_string).collect()),
+            Notes => match !new_value.is_empty() {
+                true => account.set_notes(Some(new_value)),
+                false => account.set_notes(None),
+            },
         }
     }
 
     Ok(account)
 }
 
+/// Returns the password hidden behind `*`s with its strength meter, or why it couldn't be derived
+fn masked_password(password: &std::result::Result<String, String>) -> String {
+    match password {
+        Ok(password) if password.is_empty() => String::new(),
+        Ok(password) => format!(
+            "{}  {}",
+            password.chars().map(|_| '*').collect::<String>(),
+            estimate(password).meter()
+        ),
+        Err(error) => error.to_string(),
+    }
+}
+
 /// Draws the view page to the terminal
 ///
 /// # Arguments
 ///
 /// * `account`       - The account to view
+/// * `password`      - The account's password, or why it couldn't be derived
+/// * `pending`       - The password the account is being rotated to, if it's being rotated
 /// * `current_field` - The current selected field
-fn draw_view(account: &Account, current_field: AccountField) -> Result<()> {
+fn draw_view(
+    account: &Account,
+    password: &std::result::Result<String, String>,
+    pending: &Option<std::result::Result<String, String>>,
+    current_field: AccountField,
+) -> Result<()> {
     // Setting up the terminal screen
     execute!(
         stdout(),
@@ -272,10 +702,93 @@ fn draw_view(account: &Account, current_field: AccountField) -> Result<()> {
             None => "".to_string(),
         }
     ))?;
-    println(format!(
-        " Password  {}",
-        account.password().chars().map(|_| '*').collect::<String>()
-    ))?;
+    println(format!(" Password  {}", masked_password(password)))?;
+    println(format!(" URL  {}", account.url().unwrap_or_default()))?;
+    println(format!(" Tags  {}", account.tags().join(", ")))?;
+    println(format!(" Notes  {}", account.notes().unwrap_or_default()))?;
+    if let Some(pending) = pending {
+        execute!(stdout(), cursor::MoveTo(0, PENDING_LINE))?;
+        println(format!(
+            " Pending  {}  (c confirm · X cancel · Y yank)",
+            masked_password(pending)
+        ))?;
+    }
+    if let Some(derivation) = account.derivation() {
+        execute!(stdout(), cursor::MoveTo(0, derivation_line(account)))?;
+        println(format!(
+            " Derived from  {} · {} · #{}",
+            derivation.site(),
+            derivation.login(),
+            derivation.counter()
+        ))?;
+    }
+    if let Some(policy) = account.policy() {
+        execute!(stdout(), cursor::MoveTo(0, policy_line(account)))?;
+        let compliance = match password.as_ref().map(|x| policy.check(x)) {
+            Ok(Err(error)) => format!("  (password {})", error),
+            _ => String::new(),
+        };
+        println(format!(" Policy  {}{}", policy.summary(), compliance))?;
+    }
+    if let Some(script) = account.rotation_script() {
+        execute!(stdout(), cursor::MoveTo(0, script_line(account)))?;
+        let last_run = match account.last_script_run() {
+            Some(ScriptRun {
+                time,
+                result: Ok(()),
+            }) => {
+                format!(" · rotated {}", format_date(time))
+            }
+            Some(ScriptRun {
+                time,
+                result: Err(error),
+            }) => format!(" · failed {}: {}", format_date(time), error),
+            None => String::new(),
+        };
+        println(format!(
+            " Rotation script  {} · every {} days{}",
+            script.command(),
+            script.interval(),
+            last_run
+        ))?;
+    }
+
+    if let Some(expiry) = account.expiry() {
+        execute!(stdout(), cursor::MoveTo(0, expiry_line(account)))?;
+        let expiry = match expiry {
+            Expiry::On(_) => expiry.to_string(),
+            Expiry::Every(_) => format!("every {}", expiry),
+        };
+        let left = account
+            .expires_at()
+            .map(|expires| format!(" · {}", describe_expiry(expires, time::now())))
+            .unwrap_or_default();
+        println(format!(" Expires  {}{}", expiry, left))?;
+    }
+
+    if let Some(deletes_on) = account.deletes_on() {
+        execute!(stdout(), cursor::MoveTo(0, temporary_line(account)))?;
+        println(format!(
+            " Temporary  deleted on {} · {}",
+            format_date(deletes_on),
+            describe_expiry(deletes_on, time::now())
+        ))?;
+    }
+
+    let times = [
+        ("created", account.created()),
+        ("modified", account.modified()),
+        ("password changed", account.password_changed()),
+        ("last used", account.last_used()),
+    ];
+    let times: Vec<String> = times
+        .into_iter()
+        .filter_map(|(name, time)| Some(format!("{} {}", name, format_date(time?))))
+        .collect();
+    if !times.is_empty() {
+        execute!(stdout(), cursor::MoveTo(0, times_line(account)))?;
+        println(format!(" {}", times.join(" · ")).dimmed())?;
+    }
 
     // Replacing the current fields normal label with the selected field version
     match current_field {
@@ -295,6 +808,18 @@ fn draw_view(account: &Account, current_field: AccountField) -> Result<()> {
             execute!(stdout(), cursor::MoveTo(0, 3))?;
             print(box_label("Password"))?;
         }
+        Url => {
+            execute!(stdout(), cursor::MoveTo(0, 4))?;
+            print(box_label("URL"))?;
+        }
+        Tags => {
+            execute!(stdout(), cursor::MoveTo(0, 5))?;
+            print(box_label("Tags"))?;
+        }
+        Notes => {
+            execute!(stdout(), cursor::MoveTo(0, 6))?;
+            print(box_label("Notes"))?;
+        }
     };
 
     Ok(())
@@ -0,0 +1,607 @@
+//! A module that looks through every account in the vault for passwords and details that need
+//! attention. Findings never include passwords, so the audit can be shown on screen safely
+
+mod report;
+
+pub use report::{Report, ReportFormat};
+
+use std::collections::HashMap;
+
+use crate::account::Account;
+use crate::breach::BreachList;
+use crate::strength::estimate;
+use crate::time::days_between;
+
+/// Passwords scoring below this are reported as weak
+const DEFAULT_MIN_SCORE: u8 = 3;
+/// Passwords unchanged for longer than this many days are reported as old
+const DEFAULT_MAX_AGE: u64 = 365;
+/// How alike two normalised passwords must be, from 0 to 1, to be reported as similar
+const SIMILARITY_THRESHOLD: f64 = 0.8;
+/// Normalised passwords shorter than this are too short to compare meaningfully
+const MIN_NORMALISED_LENGTH: usize = 4;
+
+/// A struct that stores the thresholds the audit reports passwords against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuditOptions {
+    min_score: u8,
+    max_age: u64,
+}
+
+impl AuditOptions {
+    /// Returns the strength score, from 0 to 4, passwords below are reported as weak
+    pub fn min_score(&self) -> u8 {
+        self.min_score
+    }
+
+    /// Sets the strength score passwords below are reported as weak
+    pub fn set_min_score(&mut self, min_score: u8) {
+        self.min_score = min_score;
+    }
+
+    /// Returns how many days a password can go unchanged, or an account unused, before it's
+    /// reported
+    pub fn max_age(&self) -> u64 {
+        self.max_age
+    }
+
+    /// Sets how many days a password can go unchanged, or an account unused, before it's reported
+    pub fn set_max_age(&mut self, max_age: u64) {
+        self.max_age = max_age;
+    }
+}
+
+impl Default for AuditOptions {
+    fn default() -> Self {
+        Self {
+            min_score: DEFAULT_MIN_SCORE,
+            max_age: DEFAULT_MAX_AGE,
+        }
+    }
+}
+
+/// Enum of the kinds of problem the audit looks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Issue {
+    /// The password has been seen in a data breach
+    Breached,
+    /// The password is used by more than one account
+    Reused,
+    /// The password is trivially derivable from another account's password
+    Similar,
+    /// The password is easy to guess
+    Weak,
+    /// The password hasn't changed in a long time
+    Old,
+    /// The account hasn't been used in a long time
+    Unused,
+    /// The account has neither a username nor an email
+    NoLogin,
+    /// The account has no password
+    Empty,
+}
+
+impl Issue {
+    /// Every kind of problem, in the order they're reported
+    pub const ALL: [Issue; 8] = [
+        Issue::Breached,
+        Issue::Reused,
+        Issue::Similar,
+        Issue::Weak,
+        Issue::Old,
+        Issue::Unused,
+        Issue::NoLogin,
+        Issue::Empty,
+    ];
+
+    /// Returns the heading the issue's findings are listed under
+    pub fn title(&self) -> &'static str {
+        match self {
+            Issue::Breached => "Breached passwords",
+            Issue::Reused => "Reused passwords",
+            Issue::Similar => "Similar passwords",
+            Issue::Weak => "Weak passwords",
+            Issue::Old => "Old passwords",
+            Issue::Unused => "Unused accounts",
+            Issue::NoLogin => "Missing username or email",
+            Issue::Empty => "Empty passwords",
+        }
+    }
+}
+
+/// An account the audit found a problem with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// The index of the account
+    pub index: usize,
+    /// More about the problem, which never contains the password
+    pub detail: String,
+}
+
+/// A struct that stores what the audit found, grouped by the kind of problem
+#[derive(Debug, Clone, Default)]
+pub struct Audit {
+    sections: Vec<(Issue, Vec<Finding>)>,
+    breaches_checked: bool,
+}
+
+impl Audit {
+    /// Audits the accounts
+    ///
+    /// # Arguments
+    ///
+    /// * `accounts` - The accounts to audit
+    /// * `master`   - The master password derived passwords come from
+    /// * `options`  - The thresholds to report passwords against
+    /// * `now`      - The current time, in seconds since the Unix epoch
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::account::Account;
+    /// # use password_manager::audit::{Audit, AuditOptions, Issue};
+    /// let accounts = vec![
+    ///     Account::builder().label("a").username("me").password("hunter2").build(),
+    ///     Account::builder().label("b").username("me").password("hunter2").build(),
+    /// ];
+    ///
+    /// let audit = Audit::run(&accounts, "master", &AuditOptions::default(), 0);
+    /// assert_eq!(audit.findings(Issue::Reused).len(), 2);
+    /// assert_eq!(audit.findings(Issue::NoLogin).len(), 0);
+    /// ```
+    pub fn run(accounts: &[Account], master: &str, options: &AuditOptions, now: u64) -> Self {
+        // Derived passwords are slow to work out, so each is only worked out once
+        let passwords: Vec<Option<String>> = accounts
+            .iter()
+            .map(|account| account.resolve_password(master).ok())
+            .collect();
+
+        let mut audit = Self::default();
+        audit.push(Issue::Reused, reused(accounts, &passwords));
+        audit.push(Issue::Similar, similar(accounts, &passwords));
+
+        let mut weak = vec![];
+        let mut empty = vec![];
+        for (index, password) in passwords.iter().enumerate() {
+            match password.as_deref() {
+                Some("") => empty.push(Finding {
+                    index,
+                    detail: String::new(),
+                }),
+                Some(password) => {
+                    let estimate = estimate(password);
+                    if estimate.score() < options.min_score() {
+                        let detail = match estimate.warning() {
+                            Some(warning) => format!("{} · {}", estimate.label(), warning),
+                            None => estimate.label().to_string(),
+                        };
+                        weak.push(Finding { index, detail });
+                    }
+                }
+                None => (),
+            }
+        }
+        audit.push(Issue::Weak, weak);
+
+        // Passwords that haven't changed since times started being recorded are counted from when
+        // the account was created, the same as unused accounts
+        let old = accounts
+            .iter()
+            .enumerate()
+            .filter_map(|(index, account)| {
+                let changed = account.password_changed().or(account.created())?;
+                let days = days_between(changed, now);
+                (days > options.max_age()).then(|| Finding {
+                    index,
+                    detail: format!("unchanged for {} days", days),
+                })
+            })
+            .collect();
+        audit.push(Issue::Old, old);
+
+        // Accounts that have never been used are counted from when they were created
+        let unused = accounts
+            .iter()
+            .enumerate()
+            .filter_map(|(index, account)| {
+                let (days, detail) = match account.last_used() {
+                    Some(used) => (days_between(used, now), "not used for"),
+                    None => (days_between(account.created()?, now), "never used in"),
+                };
+                (days > options.max_age()).then(|| Finding {
+                    index,
+                    detail: format!("{} {} days", detail, days),
+                })
+            })
+            .collect();
+        audit.push(Issue::Unused, unused);
+
+        let no_login = accounts
+            .iter()
+            .enumerate()
+            .filter(|(_, account)| account.username().is_none() && account.email().is_none())
+            .map(|(index, _)| Finding {
+                index,
+                detail: String::new(),
+            })
+            .collect();
+        audit.push(Issue::NoLogin, no_login);
+        audit.push(Issue::Empty, empty);
+
+        audit
+    }
+
+    /// Adds the accounts whose passwords appear in a list of breached passwords to the audit
+    ///
+    /// # Arguments
+    ///
+    /// * `accounts`    - The accounts that were audited
+    /// * `master`      - The master password derived passwords come from
+    /// * `breach_list` - The list of breached passwords
+    ///
+    /// # Returns
+    ///
+    /// An error if the list couldn't be read
+    pub fn check_breaches(
+        &mut self,
+        accounts: &[Account],
+        master: &str,
+        breach_list: &BreachList,
+    ) -> Result<(), String> {
+        let findings: Vec<Finding> = breach_list
+            .check_accounts(accounts, master)?
+            .into_iter()
+            .map(|breach| Finding {
+                index: breach.index,
+                detail: format!("seen {} times", breach.count),
+            })
+            .collect();
+
+        // Breached passwords are the most urgent, so they go first
+        if !findings.is_empty() {
+            self.sections.insert(0, (Issue::Breached, findings));
+        }
+        self.breaches_checked = true;
+        Ok(())
+    }
+
+    /// Returns every kind of problem that was found, along with the accounts it was found in
+    pub fn sections(&self) -> &[(Issue, Vec<Finding>)] {
+        &self.sections
+    }
+
+    /// Returns the accounts the given kind of problem was found in
+    ///
+    /// # Arguments
+    ///
+    /// * `issue` - The kind of problem
+    pub fn findings(&self, issue: Issue) -> &[Finding] {
+        self.sections
+            .iter()
+            .find(|(x, _)| *x == issue)
+            .map(|(_, findings)| findings.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Returns whether the passwords were checked against a list of breached passwords
+    pub fn breaches_checked(&self) -> bool {
+        self.breaches_checked
+    }
+
+    /// Returns whether nothing was found
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    /// Adds a section of findings, if there are any
+    fn push(&mut self, issue: Issue, findings: Vec<Finding>) {
+        if !findings.is_empty() {
+            self.sections.push((issue, findings));
+        }
+    }
+}
+
+/// Finds every account that uses the given password, for when it has to be changed everywhere
+///
+/// # Arguments
+///
+/// * `accounts` - The accounts to look through
+/// * `password` - The password to look for
+/// * `master`   - The master password derived passwords come from
+///
+/// # Returns
+///
+/// The indices of the accounts using the password, which is empty for an empty password
+///
+/// # Example
+///
+/// ```
+/// # use password_manager::account::Account;
+/// # use password_manager::audit::accounts_using;
+/// let accounts = vec![
+///     Account::builder().label("a").password("hunter2").build(),
+///     Account::builder().label("b").password("other").build(),
+///     Account::builder().label("c").password("hunter2").build(),
+/// ];
+/// assert_eq!(accounts_using(&accounts, "hunter2", "master"), vec![0, 2]);
+/// ```
+pub fn accounts_using(accounts: &[Account], password: &str, master: &str) -> Vec<usize> {
+    if password.is_empty() {
+        return vec![];
+    }
+
+    accounts
+        .iter()
+        .enumerate()
+        .filter(|(_, account)| account.resolve_password(master).as_deref() == Ok(password))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Finds the accounts that share a password with another account
+///
+/// # Arguments
+///
+/// * `accounts`  - The accounts being audited
+/// * `passwords` - The password of each account, if it could be worked out
+fn reused(accounts: &[Account], passwords: &[Option<String>]) -> Vec<Finding> {
+    let mut groups: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, password) in passwords.iter().enumerate() {
+        if let Some(password) = password.as_deref().filter(|x| !x.is_empty()) {
+            groups.entry(password).or_default().push(index);
+        }
+    }
+
+    group_findings(accounts, groups.into_values().collect(), "shared with")
+}
+
+/// Finds the accounts whose passwords are only slightly different from another account's, such
+/// as `Summer2023!` and `summer2024`. Passwords that are exactly the same are left to `reused`
+///
+/// # Arguments
+///
+/// * `accounts`  - The accounts being audited
+/// * `passwords` - The password of each account, if it could be worked out
+fn similar(accounts: &[Account], passwords: &[Option<String>]) -> Vec<Finding> {
+    let normalised: Vec<Option<Vec<char>>> = passwords
+        .iter()
+        .map(|password| {
+            password
+                .as_deref()
+                .map(normalise)
+                .filter(|x| x.len() >= MIN_NORMALISED_LENGTH)
+        })
+        .collect();
+
+    // Similar pairs are joined into groups, so a password similar to two others puts all three
+    // in the same group
+    let mut parents: Vec<usize> = (0..passwords.len()).collect();
+
+    for i in 0..normalised.len() {
+        for j in (i + 1)..normalised.len() {
+            let (Some(x), Some(y)) = (&normalised[i], &normalised[j]) else {
+                continue;
+            };
+            if passwords[i] != passwords[j] && similarity(x, y) >= SIMILARITY_THRESHOLD {
+                let (i, j) = (root(&mut parents, i), root(&mut parents, j));
+                parents[j] = i;
+            }
+        }
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for index in 0..passwords.len() {
+        groups
+            .entry(root(&mut parents, index))
+            .or_default()
+            .push(index);
+    }
+
+    group_findings(accounts, groups.into_values().collect(), "similar to")
+}
+
+/// Returns the account that represents the group the account is in, shortening the path to it
+/// along the way
+///
+/// # Arguments
+///
+/// * `parents` - The account each account was joined to, or itself if it represents its group
+/// * `index`   - The account to find the group of
+fn root(parents: &mut [usize], mut index: usize) -> usize {
+    while parents[index] != index {
+        parents[index] = parents[parents[index]];
+        index = parents[index];
+    }
+    index
+}
+
+/// Lowercases the password and strips any digits and symbols from its end, which is where years
+/// and counters are usually added
+fn normalise(password: &str) -> Vec<char> {
+    let mut normalised: Vec<char> = password.to_lowercase().chars().collect();
+    while normalised.last().is_some_and(|c| !c.is_alphabetic()) {
+        normalised.pop();
+    }
+    normalised
+}
+
+/// Returns how alike two passwords are, from 0 for nothing in common to 1 for the same
+fn similarity(x: &[char], y: &[char]) -> f64 {
+    let longest = x.len().max(y.len());
+    match longest {
+        0 => 1.0,
+        _ => 1.0 - edit_distance(x, y) as f64 / longest as f64,
+    }
+}
+
+/// Returns how many characters have to be inserted, removed or replaced to turn one password into
+/// the other
+fn edit_distance(x: &[char], y: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=y.len()).collect();
+    for (i, a) in x.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in y.iter().enumerate() {
+            let replace = previous[j] + usize::from(a != b);
+            current.push(replace.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[y.len()]
+}
+
+/// Turns groups of accounts into findings that name the other accounts in each account's group
+///
+/// # Arguments
+///
+/// * `accounts` - The accounts being audited
+/// * `groups`   - The groups of account indices, where groups of one are ignored
+/// * `relation` - How each account relates to the others in its group
+fn group_findings(accounts: &[Account], groups: Vec<Vec<usize>>, relation: &str) -> Vec<Finding> {
+    let mut groups: Vec<Vec<usize>> = groups.into_iter().filter(|x| x.len() > 1).collect();
+    groups.sort();
+
+    groups
+        .iter()
+        .flat_map(|group| {
+            group.iter().map(|index| {
+                let others: Vec<String> = group
+                    .iter()
+                    .filter(|x| *x != index)
+                    .map(|x| accounts[*x].label())
+                    .collect();
+                Finding {
+                    index: *index,
+                    detail: format!("{} {}", relation, others.join(", ")),
+                }
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Audit, AuditOptions, Issue};
+    use crate::account::{Account, Metadata};
+    use crate::time::DAY;
+
+    #[test]
+    /// Tests that passwords with no recorded change are aged from when the account was created
+    fn audit_ages_unchanged_passwords_from_creation() {
+        let accounts = vec![
+            Account::builder()
+                .label("legacy")
+                .username("me")
+                .password("Tr0ub4dor&3-horse-Zebra!")
+                .created(0)
+                .build(),
+            Account::builder()
+                .label("unknown")
+                .username("me")
+                .password("k#9Vq2!xTz@4Lm8w")
+                .build(),
+        ];
+
+        let audit = Audit::run(&accounts, "master", &AuditOptions::default(), 400 * DAY);
+        let old: Vec<usize> = audit.findings(Issue::Old).iter().map(|x| x.index).collect();
+        assert_eq!(old, vec![0]);
+    }
+
+    #[test]
+    /// Tests that each kind of problem is found in the right accounts
+    fn audit_finds_problems() {
+        let mut old = Metadata::default();
+        old.set("password_changed", 0);
+        old.set("last_used", 0);
+
+        let accounts = vec![
+            Account::builder()
+                .label("reused one")
+                .email("me@example.com")
+                .password("k#9Vq2!xTz@4Lm8w")
+                .build(),
+            Account::builder()
+                .label("reused two")
+                .username("me")
+                .password("k#9Vq2!xTz@4Lm8w")
+                .build(),
+            Account::builder()
+                .label("weak")
+                .username("me")
+                .password("password1")
+                .build(),
+            Account::builder()
+                .label("old")
+                .username("me")
+                .password("Tr0ub4dor&3-horse-Zebra!")
+                .metadata(old.to_line())
+                .build(),
+            Account::builder().label("empty").build(),
+        ];
+
+        let audit = Audit::run(&accounts, "master", &AuditOptions::default(), 400 * DAY);
+        let indices = |issue: Issue| -> Vec<usize> {
+            audit.findings(issue).iter().map(|x| x.index).collect()
+        };
+
+        assert_eq!(indices(Issue::Reused), vec![0, 1]);
+        assert_eq!(
+            audit.findings(Issue::Reused)[0].detail,
+            "shared with reused two"
+        );
+        assert_eq!(indices(Issue::Weak), vec![2]);
+        assert_eq!(indices(Issue::Old), vec![3]);
+        assert_eq!(indices(Issue::Unused), vec![3]);
+        assert_eq!(
+            audit.findings(Issue::Unused)[0].detail,
+            "not used for 400 days"
+        );
+        assert_eq!(indices(Issue::NoLogin), vec![4]);
+        assert_eq!(indices(Issue::Empty), vec![4]);
+
+        for (_, findings) in audit.sections() {
+            for finding in findings {
+                assert!(!finding.detail.contains("k#9Vq2"));
+            }
+        }
+    }
+
+    #[test]
+    /// Tests that passwords differing by a year or case are grouped, without exact reuse or
+    /// unrelated passwords being included
+    fn audit_finds_similar_passwords() {
+        let accounts: Vec<Account> = [
+            "Summer2023!",
+            "summer2024",
+            "Summer2023!",
+            "Winter2023!",
+            "sUmmer25",
+            "2023",
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, password)| {
+            Account::builder()
+                .label(i)
+                .username("me")
+                .password(password)
+                .build()
+        })
+        .collect();
+
+        let audit = Audit::run(&accounts, "master", &AuditOptions::default(), 0);
+        let similar: Vec<usize> = audit
+            .findings(Issue::Similar)
+            .iter()
+            .map(|x| x.index)
+            .collect();
+
+        assert_eq!(similar, vec![0, 1, 2, 4]);
+        assert_eq!(
+            audit.findings(Issue::Similar)[1].detail,
+            "similar to 0, 2, 4"
+        );
+        for finding in audit.findings(Issue::Similar) {
+            assert!(!finding.detail.to_lowercase().contains("summer"));
+        }
+    }
+}
@@ -0,0 +1,279 @@
+//! Audit reports, saved as a self-contained HTML page or as CSV to keep a record of security
+//! reviews. Reports name accounts but never include their passwords
+
+use std::fs;
+use std::path::Path;
+
+use super::{Audit, Issue};
+use crate::account::Account;
+use crate::time::format_utc;
+
+/// Enum of the formats a report can be saved in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Html,
+    Csv,
+}
+
+impl ReportFormat {
+    /// Picks the format from the extension of the file the report is saved to
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path the report is saved to
+    ///
+    /// # Returns
+    ///
+    /// `None` if the extension isn't `.html`, `.htm` or `.csv`, otherwise the format
+    pub fn from_path<T: AsRef<Path>>(path: T) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "html" | "htm" => Some(ReportFormat::Html),
+            "csv" => Some(ReportFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// A struct that stores everything an audit report is written from
+pub struct Report<'a> {
+    audit: &'a Audit,
+    accounts: &'a [Account],
+    generated: u64,
+}
+
+impl<'a> Report<'a> {
+    /// Creates a report of an audit
+    ///
+    /// # Arguments
+    ///
+    /// * `audit`     - What the audit found
+    /// * `accounts`  - The accounts that were audited
+    /// * `generated` - When the audit was run, in seconds since the Unix epoch
+    pub fn new(audit: &'a Audit, accounts: &'a [Account], generated: u64) -> Self {
+        Self {
+            audit,
+            accounts,
+            generated,
+        }
+    }
+
+    /// Saves the report, in the format picked by the file's extension
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to save the report to
+    ///
+    /// # Returns
+    ///
+    /// An error if the extension isn't a known format or the file couldn't be written
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let contents = match ReportFormat::from_path(path) {
+            Some(ReportFormat::Html) => self.to_html(),
+            Some(ReportFormat::Csv) => self.to_csv(),
+            None => return Err("Reports can only be saved as .html or .csv files".to_string()),
+        };
+        fs::write(path, contents).map_err(|e| format!("Couldn't save the report: {}", e))
+    }
+
+    /// Returns the details about the vault and the audit shown at the top of the report
+    fn details(&self) -> Vec<(&'static str, String)> {
+        let count = |f: fn(&Account) -> bool| self.accounts.iter().filter(|x| f(x)).count();
+
+        vec![
+            ("Generated", format_utc(self.generated)),
+            ("Version", env!("CARGO_PKG_VERSION").to_string()),
+            ("Accounts", self.accounts.len().to_string()),
+            (
+                "Derived passwords",
+                count(|x| x.derivation().is_some()).to_string(),
+            ),
+            (
+                "Accounts with a policy",
+                count(|x| x.policy().is_some()).to_string(),
+            ),
+            (
+                "Breach list checked",
+                match self.audit.breaches_checked() {
+                    true => "yes".to_string(),
+                    false => "no".to_string(),
+                },
+            ),
+        ]
+    }
+
+    /// Returns how many accounts each kind of problem was found in. Breaches are left out if they
+    /// weren't checked, so they aren't mistaken for none being found
+    fn counts(&self) -> Vec<(Issue, usize)> {
+        Issue::ALL
+            .iter()
+            .filter(|issue| **issue != Issue::Breached || self.audit.breaches_checked())
+            .map(|issue| (*issue, self.audit.findings(*issue).len()))
+            .collect()
+    }
+
+    /// Returns one row per finding, with the kind of problem, the account's label, username and
+    /// email, and more about the problem
+    fn rows(&self) -> Vec<[String; 5]> {
+        self.audit
+            .sections()
+            .iter()
+            .flat_map(|(issue, findings)| {
+                findings.iter().map(|finding| {
+                    let account = &self.accounts[finding.index];
+                    [
+                        issue.title().to_string(),
+                        account.label(),
+                        account.username().unwrap_or_default(),
+                        account.email().unwrap_or_default(),
+                        finding.detail.clone(),
+                    ]
+                })
+            })
+            .collect()
+    }
+
+    /// Writes the report as CSV. The vault details and counts come first as `name,value` rows,
+    /// followed by a blank line and a table of the findings
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::account::Account;
+    /// # use password_manager::audit::{Audit, AuditOptions, Report};
+    /// let accounts = vec![Account::builder().label("=cmd, \"x\"").build()];
+    /// let audit = Audit::run(&accounts, "master", &AuditOptions::default(), 0);
+    ///
+    /// let csv = Report::new(&audit, &accounts, 0).to_csv();
+    /// assert!(csv.starts_with("Generated,1970-01-01 00:00:00 UTC\n"));
+    /// assert!(csv.contains("Empty passwords,\"'=cmd, \"\"x\"\"\",,,\n"));
+    /// ```
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        let mut push_row = |row: &[&str]| {
+            let row: Vec<String> = row.iter().map(|x| csv_field(x)).collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        };
+
+        for (name, value) in self.details() {
+            push_row(&[name, &value]);
+        }
+        for (issue, count) in self.counts() {
+            push_row(&[issue.title(), &count.to_string()]);
+        }
+
+        push_row(&[]);
+        push_row(&["Issue", "Label", "Username", "Email", "Detail"]);
+        for row in self.rows() {
+            push_row(&row.each_ref().map(String::as_str));
+        }
+
+        csv
+    }
+
+    /// Writes the report as an HTML page with its styles inline, so it can be opened or archived
+    /// on its own
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::account::Account;
+    /// # use password_manager::audit::{Audit, AuditOptions, Report};
+    /// let accounts = vec![Account::builder().label("<b>").password("hunter2").build()];
+    /// let audit = Audit::run(&accounts, "master", &AuditOptions::default(), 0);
+    ///
+    /// let html = Report::new(&audit, &accounts, 0).to_html();
+    /// assert!(html.contains("&lt;b&gt;"));
+    /// assert!(!html.contains("hunter2"));
+    /// ```
+    pub fn to_html(&self) -> String {
+        let mut html = String::from(HTML_HEAD);
+
+        html.push_str("<h1>Password Manager Audit</h1>\n<table class=\"details\">\n");
+        for (name, value) in self.details() {
+            html.push_str(&format!(
+                "<tr><th>{}</th><td>{}</td></tr>\n",
+                name,
+                escape_html(&value)
+            ));
+        }
+        html.push_str("</table>\n\n<h2>Summary</h2>\n<table class=\"details\">\n");
+        for (issue, count) in self.counts() {
+            let class = if count > 0 { " class=\"found\"" } else { "" };
+            html.push_str(&format!(
+                "<tr><th>{}</th><td{}>{}</td></tr>\n",
+                issue.title(),
+                class,
+                count
+            ));
+        }
+        html.push_str("</table>\n");
+
+        if self.audit.is_empty() {
+            html.push_str("\n<p>No problems found.</p>\n");
+        }
+        for (issue, findings) in self.audit.sections() {
+            html.push_str(&format!(
+                "\n<h2>{}</h2>\n<table>\n\
+                 <tr><th>Label</th><th>Username</th><th>Email</th><th>Detail</th></tr>\n",
+                issue.title()
+            ));
+            for finding in findings {
+                let account = &self.accounts[finding.index];
+                html.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    escape_html(&account.label()),
+                    escape_html(&account.username().unwrap_or_default()),
+                    escape_html(&account.email().unwrap_or_default()),
+                    escape_html(&finding.detail)
+                ));
+            }
+            html.push_str("</table>\n");
+        }
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+/// The start of the HTML report, up to the opening of the body
+const HTML_HEAD: &str = "<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+<meta charset=\"utf-8\">
+<title>Password Manager Audit</title>
+<style>
+body { font-family: sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; margin-bottom: 1em; }
+th, td { border: 1px solid #ccc; padding: 0.3em 0.8em; text-align: left; }
+th { background: #f0f0f0; }
+.details th { width: 14em; }
+.found { color: #b00; font-weight: bold; }
+</style>
+</head>
+<body>
+";
+
+/// Quotes a CSV field if it needs to be. Fields that a spreadsheet would run as a formula are
+/// prefixed with `'`, so a label can't be used to run anything when the report is opened
+fn csv_field(field: &str) -> String {
+    let field = match field.starts_with(['=', '+', '-', '@']) {
+        true => format!("'{}", field),
+        false => field.to_string(),
+    };
+
+    match field.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field,
+    }
+}
+
+/// Escapes the characters that HTML would read as markup
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
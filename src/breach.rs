@@ -0,0 +1,254 @@
+//! A module that checks passwords against the Pwned Passwords list of breached passwords, without
+//! going over the network
+//!
+//! # Format
+//!
+//! Either of the forms Pwned Passwords can be downloaded in is accepted:
+//!
+//! * A single file of `HASH:COUNT` lines sorted by hash, where `HASH` is the uppercase SHA-1 of a
+//!   password. The file is binary searched, so it doesn't have to fit in memory
+//! * A directory of range files, each named after the first 5 characters of the hashes it holds
+//!   and containing `SUFFIX:COUNT` lines for the other 35 characters
+
+use sha1::{Digest, Sha1};
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use crate::account::Account;
+
+/// How many characters of the hash name the range file it's in
+const PREFIX_LENGTH: usize = 5;
+
+/// A struct that stores where a list of breached passwords is
+#[derive(Debug, Clone)]
+pub struct BreachList {
+    path: PathBuf,
+}
+
+/// An account whose password appears in the breach list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Breach {
+    /// The index of the account
+    pub index: usize,
+    /// How many times the password has been seen in breaches
+    pub count: u64,
+}
+
+impl BreachList {
+    /// Opens a breach list
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the sorted hash file or the directory of range files
+    ///
+    /// # Returns
+    ///
+    /// Either the list, or an error if there's nothing at the path
+    pub fn open<T: AsRef<Path>>(path: T) -> Result<Self, String> {
+        let path = path.as_ref();
+        match path.exists() {
+            true => Ok(Self {
+                path: path.to_path_buf(),
+            }),
+            false => Err(format!("The breach list {} doesn't exist", path.display())),
+        }
+    }
+
+    /// Looks up how many times the password has been seen in breaches
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password to look up
+    ///
+    /// # Returns
+    ///
+    /// `None` if the password hasn't been seen, otherwise how many times it has, or an error if
+    /// the list couldn't be read
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::breach::BreachList;
+    /// let path = std::env::temp_dir().join("breach_list_doctest.txt");
+    /// std::fs::write(
+    ///     &path,
+    ///     "5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8:9545824\r\n\
+    ///      7C4A8D09CA3762AF61E59520943DC26494F8941B:37359195\r\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// let list = BreachList::open(&path).unwrap();
+    /// assert_eq!(list.occurrences("password"), Ok(Some(9545824)));
+    /// assert_eq!(list.occurrences("123456"), Ok(Some(37359195)));
+    /// assert_eq!(list.occurrences("not in the list"), Ok(None));
+    /// ```
+    pub fn occurrences(&self, password: &str) -> Result<Option<u64>, String> {
+        let hash = sha1_hex(password);
+        let result = match self.path.is_dir() {
+            true => self.search_range(&hash),
+            false => self.search_sorted(&hash),
+        };
+        result.map_err(|e| format!("Couldn't read the breach list: {}", e))
+    }
+
+    /// Checks the password of every account against the list
+    ///
+    /// # Arguments
+    ///
+    /// * `accounts` - The accounts to check
+    /// * `master`   - The master password derived passwords come from
+    ///
+    /// # Returns
+    ///
+    /// The accounts whose passwords have been seen in breaches, most seen first, or an error if
+    /// the list couldn't be read
+    pub fn check_accounts(
+        &self,
+        accounts: &[Account],
+        master: &str,
+    ) -> Result<Vec<Breach>, String> {
+        let mut breaches = vec![];
+        for (index, account) in accounts.iter().enumerate() {
+            let Ok(password) = account.resolve_password(master) else {
+                continue;
+            };
+            if password.is_empty() {
+                continue;
+            }
+            if let Some(count) = self.occurrences(&password)? {
+                breaches.push(Breach { index, count });
+            }
+        }
+        breaches.sort_by_key(|breach| std::cmp::Reverse(breach.count));
+        Ok(breaches)
+    }
+
+    /// Looks for the hash in the range file named after its prefix
+    fn search_range(&self, hash: &str) -> std::io::Result<Option<u64>> {
+        let (prefix, suffix) = hash.split_at(PREFIX_LENGTH);
+        let file = match ["", ".txt"]
+            .iter()
+            .map(|extension| self.path.join(format!("{}{}", prefix, extension)))
+            .find(|path| path.exists())
+        {
+            Some(file) => File::open(file)?,
+            None => return Ok(None),
+        };
+
+        for line in BufReader::new(file).lines() {
+            if let Some(count) = parse_line(&line?, suffix) {
+                return Ok(Some(count));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Binary searches the sorted hash file for the hash. Lines can be different lengths, so each
+    /// probe seeks to a byte and reads the first whole line after it
+    fn search_sorted(&self, hash: &str) -> std::io::Result<Option<u64>> {
+        let mut file = BufReader::new(File::open(&self.path)?);
+        let (mut low, mut high) = (0, file.get_ref().metadata()?.len());
+        let mut line = String::new();
+
+        // Only lines starting in low..high can still hold the hash
+        while low < high {
+            let middle = low + (high - low) / 2;
+
+            let mut start = middle;
+            if middle > 0 {
+                file.seek(SeekFrom::Start(middle - 1))?;
+                line.clear();
+                start += file.read_line(&mut line)? as u64 - 1;
+            } else {
+                file.seek(SeekFrom::Start(0))?;
+            }
+            if start >= high {
+                high = middle;
+                continue;
+            }
+
+            line.clear();
+            let length = file.read_line(&mut line)? as u64;
+            let line_hash = line.split(':').next().unwrap_or("").trim();
+            match line_hash.cmp(hash) {
+                Ordering::Equal => return Ok(parse_line(&line, hash)),
+                Ordering::Less => low = start + length,
+                Ordering::Greater => high = middle,
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Returns the count on the line if it's for the hash
+///
+/// # Arguments
+///
+/// * `line` - A `HASH:COUNT` line
+/// * `hash` - The hash, or the part of it, the line should be for
+fn parse_line(line: &str, hash: &str) -> Option<u64> {
+    let (line_hash, count) = line.trim().split_once(':')?;
+    match line_hash.eq_ignore_ascii_case(hash) {
+        true => count.trim().parse().ok(),
+        false => None,
+    }
+}
+
+/// Returns the uppercase hex SHA-1 of the password, as used by Pwned Passwords
+fn sha1_hex(password: &str) -> String {
+    Sha1::digest(password.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sha1_hex, BreachList};
+    use std::fs;
+
+    #[test]
+    /// Tests that every hash in a sorted file is found, and ones between them aren't
+    fn sorted_file_is_searched() {
+        let passwords: Vec<String> = (0..200).map(|x| format!("password{}", x)).collect();
+        let mut lines: Vec<String> = passwords
+            .iter()
+            .enumerate()
+            .map(|(count, password)| format!("{}:{}", sha1_hex(password), count + 1))
+            .collect();
+        lines.sort();
+
+        let path = std::env::temp_dir().join("breach_list_sorted_test.txt");
+        fs::write(&path, lines.join("\n")).unwrap();
+        let list = BreachList::open(&path).unwrap();
+
+        for (count, password) in passwords.iter().enumerate() {
+            assert_eq!(list.occurrences(password), Ok(Some(count as u64 + 1)));
+        }
+        assert_eq!(list.occurrences("password200"), Ok(None));
+        assert_eq!(list.occurrences(""), Ok(None));
+    }
+
+    #[test]
+    /// Tests that hashes are found in the range file named after their prefix
+    fn range_directory_is_searched() {
+        let directory = std::env::temp_dir().join("breach_list_range_test");
+        fs::create_dir_all(&directory).unwrap();
+
+        let hash = sha1_hex("hunter2");
+        let (prefix, suffix) = hash.split_at(5);
+        fs::write(
+            directory.join(prefix),
+            format!("0000000000000000000000000000000000A:1\r\n{}:42\r\n", suffix),
+        )
+        .unwrap();
+
+        let list = BreachList::open(&directory).unwrap();
+        assert_eq!(list.occurrences("hunter2"), Ok(Some(42)));
+        assert_eq!(list.occurrences("hunter3"), Ok(None));
+        assert!(BreachList::open(directory.join("missing")).is_err());
+    }
+}
@@ -0,0 +1,259 @@
+//! This module contains the clipboard used to yank account fields
+//!
+//! Some platforms only keep a value in the clipboard for as long as the program that put it there
+//! keeps serving it, so every yank hands the value to a background thread that owns the clipboard
+//! backend. The thread serves the value until the timeout runs out or the value is replaced, and
+//! then clears the clipboard, but only if it still contains the yanked value
+//!
+//! The OSC 52 backend is the exception. Terminals don't let their clipboard be read back, so
+//! there's no telling whether the value is still there, and clearing it could wipe something
+//! copied since. Values yanked with it are never cleared, and the status line says so
+//!
+//! If no backend can be used, the value is shown on screen once instead
+mod backend;
+
+pub use backend::BackendKind;
+
+use backend::write_osc52;
+
+use crossterm::{
+    cursor,
+    event::{poll, read, Event},
+    execute,
+    style::Print,
+    terminal::{self, Clear, ClearType},
+    Result,
+};
+use std::io::stdout;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// How often the countdown on the status line is redrawn while waiting for a key press
+const TICK: Duration = Duration::from_millis(250);
+
+/// A value that is currently being served by a background thread
+struct ActiveYank {
+    /// When the clipboard will be cleared
+    expires: Instant,
+    /// Tells the thread to stop serving the value, and whether to clear the clipboard when it does
+    stop: Sender<bool>,
+    /// The background thread
+    handle: JoinHandle<()>,
+}
+
+/// A clipboard that clears itself a set amount of time after a value is yanked into it
+pub struct Clipboard {
+    timeout: Duration,
+    backend: BackendKind,
+    active: Option<ActiveYank>,
+    /// Whether the last value was yanked somewhere it won't be cleared from
+    uncleared: bool,
+}
+
+impl Clipboard {
+    /// Creates a new Clipboard
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long yanked values stay in the clipboard
+    /// * `backend` - The backend to use, or `None` to pick one that works in this environment
+    pub fn new(timeout: Duration, backend: Option<BackendKind>) -> Self {
+        Self {
+            timeout,
+            backend: backend.unwrap_or_else(BackendKind::detect),
+            active: None,
+            uncleared: false,
+        }
+    }
+
+    /// Yanks the given text into the clipboard, replacing any value that was yanked before it. If
+    /// the clipboard can't be used the text is shown on screen once instead
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to yank
+    pub fn yank(&mut self, text: String) -> Result<()> {
+        self.stop(false);
+        self.uncleared = false;
+
+        match self.backend {
+            BackendKind::Screen => return show_once(&text, "No clipboard is available"),
+            // Written here rather than by a background thread, so it's never drawn over
+            BackendKind::Osc52 => {
+                return match write_osc52(&text) {
+                    Ok(()) => {
+                        self.uncleared = true;
+                        Ok(())
+                    }
+                    Err(e) => show_once(&text, &format!("Couldn't access the clipboard: {}", e)),
+                };
+            }
+            _ => (),
+        }
+
+        let (stop, stop_receiver) = mpsc::channel();
+        let (ready, ready_receiver) = mpsc::channel();
+        let timeout = self.timeout;
+        let backend = self.backend;
+        let yanked_text = text.clone();
+
+        let handle = thread::spawn(move || {
+            let mut clipboard = match backend.connect() {
+                Some(Ok(clipboard)) => clipboard,
+                Some(Err(e)) => return ready.send(Err(e)).unwrap_or(()),
+                None => return ready.send(Err("No clipboard".to_string())).unwrap_or(()),
+            };
+            if let Err(e) = clipboard.set(&yanked_text) {
+                return ready.send(Err(e)).unwrap_or(());
+            }
+            ready.send(Ok(())).unwrap_or(());
+
+            let clear = match stop_receiver.recv_timeout(timeout) {
+                Ok(clear) => clear,
+                Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => true,
+            };
+
+            // Only clearing the clipboard if something else hasn't been copied since, or if there's
+            // no way of telling
+            if clear && clipboard.get().is_none_or(|current| current == yanked_text) {
+                clipboard.clear().unwrap_or(());
+            }
+        });
+
+        match ready_receiver.recv() {
+            Ok(Ok(())) => {
+                self.active = Some(ActiveYank {
+                    expires: Instant::now() + timeout,
+                    stop,
+                    handle,
+                });
+                Ok(())
+            }
+            Ok(Err(e)) => show_once(&text, &format!("Couldn't access the clipboard: {}", e)),
+            Err(_) => show_once(&text, "Couldn't access the clipboard"),
+        }
+    }
+
+    /// Clears the clipboard now if it still contains the yanked value
+    pub fn clear(&mut self) {
+        self.stop(true);
+    }
+
+    /// Returns how long is left until the clipboard is cleared, or `None` if nothing is being
+    /// served
+    pub fn remaining(&mut self) -> Option<Duration> {
+        let remaining = self
+            .active
+            .as_ref()?
+            .expires
+            .saturating_duration_since(Instant::now());
+
+        if remaining.is_zero() {
+            self.stop(true);
+            return None;
+        }
+
+        Some(remaining)
+    }
+
+    /// Returns the text to show on the status line, if there is any
+    pub fn status(&mut self) -> Option<String> {
+        match self.remaining() {
+            Some(remaining) => Some(format!("Yanked! Clearing in {}s", remaining.as_secs() + 1)),
+            None if self.uncleared => {
+                Some("Yanked! The terminal's clipboard won't be cleared".to_string())
+            }
+            None => None,
+        }
+    }
+
+    /// Draws the clipboard's status on the last line of the terminal, leaving the cursor where it
+    /// was
+    pub fn draw_status(&mut self) -> Result<()> {
+        let (_, rows) = terminal::size()?;
+        let status = self.status().unwrap_or_default();
+        execute!(
+            stdout(),
+            cursor::SavePosition,
+            cursor::MoveTo(0, rows.saturating_sub(1)),
+            Clear(ClearType::CurrentLine),
+            Print(status),
+            cursor::RestorePosition
+        )
+    }
+
+    /// Waits for the next terminal event, keeping the countdown on the status line up to date
+    /// while a value is being served
+    pub fn read_event(&mut self) -> Result<Event> {
+        loop {
+            self.draw_status()?;
+            if self.active.is_none() || poll(TICK)? {
+                return read();
+            }
+        }
+    }
+
+    /// Keeps serving the yanked value until it times out or the user presses a key, so that it
+    /// can still be pasted after the password manager is closed
+    pub fn wait_until_cleared(&mut self) -> Result<()> {
+        while let Some(remaining) = self.remaining() {
+            execute!(
+                stdout(),
+                cursor::MoveTo(0, 0),
+                Clear(ClearType::All),
+                cursor::Hide,
+                Print(format!(
+                    "Clearing the clipboard in {}s, press any key to clear it now",
+                    remaining.as_secs() + 1
+                ))
+            )?;
+            if poll(TICK)? {
+                if let Event::Key(_) = read()? {
+                    self.clear();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Tells the background thread to stop serving the yanked value and waits for it to finish
+    ///
+    /// # Arguments
+    ///
+    /// * `clear` - Whether the clipboard should be cleared
+    fn stop(&mut self, clear: bool) {
+        if let Some(active) = self.active.take() {
+            active.stop.send(clear).unwrap_or(());
+            active.handle.join().unwrap_or(());
+        }
+    }
+}
+
+/// Shows the text on its own screen until the user presses a key, for when it can't be put into a
+/// clipboard
+///
+/// # Arguments
+///
+/// * `text`   - The text to show
+/// * `reason` - Why the text couldn't be yanked
+fn show_once(text: &str, reason: &str) -> Result<()> {
+    execute!(
+        stdout(),
+        cursor::MoveTo(0, 0),
+        Clear(ClearType::All),
+        cursor::Hide,
+        Print(format!(
+            "{}, so here it is instead:\n\r\n\r{}\n\r\n\rPress any key to hide it",
+            reason, text
+        ))
+    )?;
+    while !matches!(read()?, Event::Key(_)) {}
+    execute!(stdout(), cursor::MoveTo(0, 0), Clear(ClearType::All))
+}
+
+impl Drop for Clipboard {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
@@ -0,0 +1,246 @@
+//! This module contains the different ways a value can be put into a clipboard, and the logic for
+//! picking which one to use
+
+use std::env;
+use std::io::{self, stdout, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// The tmux buffer yanked values are loaded into, so that only our buffer is ever overwritten or
+/// deleted
+const TMUX_BUFFER: &str = "password_manager";
+
+/// Something that can hold a yanked value
+pub trait Backend {
+    /// Puts the text into the clipboard
+    fn set(&mut self, text: &str) -> Result<(), String>;
+
+    /// Returns what is currently in the clipboard, or `None` if it can't be read back
+    fn get(&mut self) -> Option<String>;
+
+    /// Empties the clipboard
+    fn clear(&mut self) -> Result<(), String>;
+}
+
+/// Enum of the available clipboard backends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackendKind {
+    /// The system clipboard, accessed directly
+    Arboard,
+    /// The `wl-copy` and `wl-paste` commands, for Wayland
+    WlCopy,
+    /// The `xclip` command, for X11
+    Xclip,
+    /// The `xsel` command, for X11
+    Xsel,
+    /// A tmux paste buffer, which tmux also forwards to the outer terminal's clipboard
+    Tmux,
+    /// The OSC 52 escape sequence, which asks the terminal itself to set its clipboard. Works
+    /// over SSH, but the clipboard can't be read back so it's never cleared
+    Osc52,
+    /// Shows the value on screen once instead of putting it anywhere
+    Screen,
+}
+
+impl BackendKind {
+    /// Returns the backend with the given name, as used in the config file
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the backend
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::clipboard::BackendKind;
+    /// assert_eq!(BackendKind::from_name("osc52"), Some(BackendKind::Osc52));
+    /// assert_eq!(BackendKind::from_name("carrier pigeon"), None);
+    /// ```
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "arboard" => Some(Self::Arboard),
+            "wl-copy" => Some(Self::WlCopy),
+            "xclip" => Some(Self::Xclip),
+            "xsel" => Some(Self::Xsel),
+            "tmux" => Some(Self::Tmux),
+            "osc52" => Some(Self::Osc52),
+            "screen" => Some(Self::Screen),
+            _ => None,
+        }
+    }
+
+    /// Picks the first backend that looks like it will work in the current environment, falling
+    /// back to showing values on screen if none will
+    pub fn detect() -> Self {
+        let has_env = |name: &str| env::var_os(name).is_some_and(|x| !x.is_empty());
+
+        if arboard::Clipboard::new().is_ok() {
+            Self::Arboard
+        } else if has_env("WAYLAND_DISPLAY") && command_exists("wl-copy") {
+            Self::WlCopy
+        } else if has_env("DISPLAY") && command_exists("xclip") {
+            Self::Xclip
+        } else if has_env("DISPLAY") && command_exists("xsel") {
+            Self::Xsel
+        } else if has_env("TMUX") && command_exists("tmux") {
+            Self::Tmux
+        } else if has_env("SSH_TTY") || has_env("SSH_CONNECTION") {
+            Self::Osc52
+        } else {
+            Self::Screen
+        }
+    }
+
+    /// Connects to the backend
+    ///
+    /// # Returns
+    ///
+    /// `None` for the screen backend, which doesn't hold values, and the OSC 52 backend, which the
+    /// clipboard writes itself with `write_osc52`. Otherwise either the backend or an error saying
+    /// why it couldn't be used
+    pub fn connect(&self) -> Option<Result<Box<dyn Backend>, String>> {
+        let backend: Box<dyn Backend> = match self {
+            Self::Arboard => match arboard::Clipboard::new() {
+                Ok(clipboard) => Box::new(ArboardBackend(clipboard)),
+                Err(e) => return Some(Err(e.to_string())),
+            },
+            Self::WlCopy => Box::new(CommandBackend {
+                copy: &["wl-copy"],
+                paste: &["wl-paste", "--no-newline"],
+                clear: Some(&["wl-copy", "--clear"]),
+            }),
+            Self::Xclip => Box::new(CommandBackend {
+                copy: &["xclip", "-selection", "clipboard"],
+                paste: &["xclip", "-selection", "clipboard", "-o"],
+                clear: None,
+            }),
+            Self::Xsel => Box::new(CommandBackend {
+                copy: &["xsel", "--clipboard", "--input"],
+                paste: &["xsel", "--clipboard", "--output"],
+                clear: Some(&["xsel", "--clipboard", "--clear"]),
+            }),
+            Self::Tmux => Box::new(CommandBackend {
+                copy: &["tmux", "load-buffer", "-b", TMUX_BUFFER, "-w", "-"],
+                paste: &["tmux", "save-buffer", "-b", TMUX_BUFFER, "-"],
+                // Deleting the buffer leaves the value in the outer terminal's clipboard, and tmux
+                // ignores empty values, so that's overwritten with a space before it's deleted
+                clear: Some(&[
+                    "tmux",
+                    "set-buffer",
+                    "-b",
+                    TMUX_BUFFER,
+                    "-w",
+                    " ",
+                    ";",
+                    "delete-buffer",
+                    "-b",
+                    TMUX_BUFFER,
+                ]),
+            }),
+            Self::Osc52 | Self::Screen => return None,
+        };
+        Some(Ok(backend))
+    }
+}
+
+/// The system clipboard through arboard. The value is only served while this is alive
+struct ArboardBackend(arboard::Clipboard);
+
+impl Backend for ArboardBackend {
+    fn set(&mut self, text: &str) -> Result<(), String> {
+        self.0.set_text(text).map_err(|e| e.to_string())
+    }
+
+    fn get(&mut self) -> Option<String> {
+        self.0.get_text().ok()
+    }
+
+    fn clear(&mut self) -> Result<(), String> {
+        self.0.clear().map_err(|e| e.to_string())
+    }
+}
+
+/// A clipboard reached through external commands, which serve the value themselves. Values are
+/// always passed through stdin so they never show up in the process list
+struct CommandBackend {
+    /// Reads the value to copy from stdin
+    copy: &'static [&'static str],
+    /// Writes the current value to stdout
+    paste: &'static [&'static str],
+    /// Empties the clipboard, or `None` to copy an empty value instead
+    clear: Option<&'static [&'static str]>,
+}
+
+impl Backend for CommandBackend {
+    fn set(&mut self, text: &str) -> Result<(), String> {
+        let mut child = Command::new(self.copy[0])
+            .args(&self.copy[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("{}: {}", self.copy[0], e))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(text.as_bytes())
+                .map_err(|e| format!("{}: {}", self.copy[0], e))?;
+        }
+
+        match child.wait() {
+            Ok(status) if status.success() => Ok(()),
+            Ok(status) => Err(format!("{} exited with {}", self.copy[0], status)),
+            Err(e) => Err(format!("{}: {}", self.copy[0], e)),
+        }
+    }
+
+    fn get(&mut self) -> Option<String> {
+        let output = Command::new(self.paste[0])
+            .args(&self.paste[1..])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+
+        match output.status.success() {
+            true => String::from_utf8(output.stdout).ok(),
+            false => None,
+        }
+    }
+
+    fn clear(&mut self) -> Result<(), String> {
+        let Some(clear) = self.clear else {
+            return self.set("");
+        };
+
+        match Command::new(clear[0])
+            .args(&clear[1..])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+        {
+            Ok(status) if status.success() => Ok(()),
+            Ok(status) => Err(format!("{} exited with {}", clear[0], status)),
+            Err(e) => Err(format!("{}: {}", clear[0], e)),
+        }
+    }
+}
+
+/// Sets the terminal's clipboard with the OSC 52 escape sequence. This has to be called from the
+/// thread drawing the UI, so the sequence can't be written in the middle of something else
+///
+/// # Arguments
+///
+/// * `text` - The text to put in the clipboard
+pub(super) fn write_osc52(text: &str) -> io::Result<()> {
+    let mut stdout = stdout().lock();
+    write!(stdout, "\x1b]52;c;{}\x07", base64::encode(text))?;
+    stdout.flush()
+}
+
+/// Returns whether an executable with the given name is on the PATH
+fn command_exists(name: &str) -> bool {
+    env::var_os("PATH")
+        .is_some_and(|path| env::split_paths(&path).any(|dir| Path::new(&dir).join(name).is_file()))
+}
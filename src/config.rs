@@ -0,0 +1,301 @@
+//! This module contains the user's configuration of the password manager
+//!
+//! # File Format
+//!
+//! The config file is made up of `key = value` lines. Blank lines and lines starting with `#`
+//! are ignored, as are unknown keys and values that can't be parsed, which keep their defaults
+//!
+//! ```text
+//! # How many seconds yanked fields stay in the clipboard
+//! clipboard_timeout = 30
+//!
+//! # Where yanked fields go. One of auto, arboard, wl-copy, xclip, xsel, tmux, osc52 or screen
+//! clipboard_backend = auto
+//!
+//! # A downloaded Pwned Passwords hash file, or directory of range files, to check passwords
+//! # against
+//! breach_file = /home/user/pwned-passwords-sha1-ordered-by-hash.txt
+//!
+//! # The audit reports passwords with a strength score, from 0 to 4, below this
+//! audit_min_score = 3
+//!
+//! # The audit reports passwords unchanged, and accounts unused, for more than this many days
+//! audit_max_age = 365
+//!
+//! # After unlocking, passwords expiring within this many days are listed
+//! expiry_warning_days = 14
+//!
+//! # Deleted accounts are kept in the trash for this many days, or forever if it's 0
+//! trash_retention_days = 30
+//!
+//! # Password generator presets, saved from the generator dialog
+//! preset.pin = length=6,digits,set=
+//! preset.memorable = passphrase,words=5,capitalise,separator=-
+//! preset.phone = pronounceable,syllables=6,capitalise,digit
+//! ```
+
+use std::fs;
+use std::io;
+use std::time::Duration;
+
+use crate::audit::AuditOptions;
+use crate::clipboard::BackendKind;
+use crate::generator::Generator;
+
+const DEFAULT_CLIPBOARD_TIMEOUT: u64 = 30;
+const DEFAULT_EXPIRY_WARNING_DAYS: u64 = 14;
+const DEFAULT_TRASH_RETENTION_DAYS: u64 = 30;
+
+/// A struct that stores the user's configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    file: Option<String>,
+    clipboard_timeout: Duration,
+    clipboard_backend: Option<BackendKind>,
+    breach_file: Option<String>,
+    audit: AuditOptions,
+    expiry_warning_days: u64,
+    trash_retention_days: u64,
+    presets: Vec<(String, Generator)>,
+}
+
+impl Config {
+    /// Loads the config from the given file, using the defaults if the file can't be read
+    ///
+    /// # Arguments
+    ///
+    /// * `config_file` - The path of the config file
+    pub fn load(config_file: &str) -> Self {
+        let mut config = match fs::read_to_string(config_file) {
+            Ok(contents) => Self::parse(&contents),
+            Err(_) => Self::default(),
+        };
+        config.file = Some(config_file.to_string());
+        config
+    }
+
+    /// Parses the contents of a config file
+    ///
+    /// # Arguments
+    ///
+    /// * `contents` - The contents of the config file
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::config::Config;
+    /// # use std::time::Duration;
+    /// let config = Config::parse("# A comment\nclipboard_timeout = 10\n");
+    /// assert_eq!(config.clipboard_timeout(), Duration::from_secs(10));
+    /// ```
+    pub fn parse(contents: &str) -> Self {
+        let mut config = Self::default();
+
+        for line in contents.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+
+            let value = value.trim();
+            match key.trim() {
+                "clipboard_timeout" => {
+                    if let Ok(seconds) = value.parse() {
+                        config.clipboard_timeout = Duration::from_secs(seconds);
+                    }
+                }
+                "clipboard_backend" => {
+                    config.clipboard_backend = BackendKind::from_name(value);
+                }
+                "breach_file" if !value.is_empty() => {
+                    config.breach_file = Some(value.to_string());
+                }
+                "audit_min_score" => {
+                    // Strength scores only go up to 4, so anything higher is ignored
+                    if let Ok(min_score @ 0..=4) = value.parse() {
+                        config.audit.set_min_score(min_score);
+                    }
+                }
+                "audit_max_age" => {
+                    if let Ok(max_age) = value.parse() {
+                        config.audit.set_max_age(max_age);
+                    }
+                }
+                "expiry_warning_days" => {
+                    if let Ok(days) = value.parse() {
+                        config.expiry_warning_days = days;
+                    }
+                }
+                "trash_retention_days" => {
+                    if let Ok(days) = value.parse() {
+                        config.trash_retention_days = days;
+                    }
+                }
+                key => {
+                    let preset = key.strip_prefix("preset.").map(str::trim);
+                    let generator = Generator::from_preset(value);
+                    if let (Some(name), Some(generator)) = (preset, generator) {
+                        config.set_preset(name, generator);
+                    }
+                }
+            }
+        }
+
+        config
+    }
+
+    /// Returns how long yanked fields stay in the clipboard before it's cleared
+    pub fn clipboard_timeout(&self) -> Duration {
+        self.clipboard_timeout
+    }
+
+    /// Returns the clipboard backend to use, or `None` if one should be picked automatically
+    pub fn clipboard_backend(&self) -> Option<BackendKind> {
+        self.clipboard_backend
+    }
+
+    /// Returns the path of the list of breached passwords, or `None` if there isn't one
+    pub fn breach_file(&self) -> Option<&str> {
+        self.breach_file.as_deref()
+    }
+
+    /// Returns the thresholds the audit reports passwords against
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::config::Config;
+    /// let config = Config::parse("audit_min_score = 4\n");
+    /// assert_eq!(config.audit_options().min_score(), 4);
+    ///
+    /// // Scores only go up to 4, so 5 keeps the default
+    /// let default = Config::default().audit_options().min_score();
+    /// let config = Config::parse("audit_min_score = 5\n");
+    /// assert_eq!(config.audit_options().min_score(), default);
+    /// ```
+    pub fn audit_options(&self) -> AuditOptions {
+        self.audit
+    }
+
+    /// Returns how many days ahead expiring passwords are warned about
+    pub fn expiry_warning_days(&self) -> u64 {
+        self.expiry_warning_days
+    }
+
+    /// Returns how many days deleted accounts are kept in the trash, where 0 keeps them forever
+    pub fn trash_retention_days(&self) -> u64 {
+        self.trash_retention_days
+    }
+
+    /// Returns the saved password generator presets, in the order they were first saved
+    pub fn presets(&self) -> &[(String, Generator)] {
+        &self.presets
+    }
+
+    /// Saves a password generator preset, replacing any preset with the same name. A preset that
+    /// is already in the config file is rewritten where it is, otherwise it's appended, so that
+    /// the rest of the file and its comments are kept
+    ///
+    /// # Arguments
+    ///
+    /// * `name`      - The name of the preset
+    /// * `generator` - The generator to save
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::config::Config;
+    /// # use password_manager::generator::{Generator, PassphraseOptions};
+    /// let path = std::env::temp_dir().join("password_manager_save_preset.txt");
+    /// std::fs::write(&path, "# Presets\npreset.memorable = length=8,set=\n").unwrap();
+    ///
+    /// let mut config = Config::load(path.to_str().unwrap());
+    /// let generator = Generator::Passphrase(PassphraseOptions::default());
+    /// config.save_preset("memorable", generator.clone()).unwrap();
+    /// config.save_preset("memorable", generator.clone()).unwrap();
+    ///
+    /// let contents = std::fs::read_to_string(&path).unwrap();
+    /// assert_eq!(
+    ///     contents,
+    ///     format!("# Presets\npreset.memorable = {}\n", generator.to_preset())
+    /// );
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn save_preset(&mut self, name: &str, generator: Generator) -> io::Result<()> {
+        if let Some(file) = &self.file {
+            let contents = match fs::read_to_string(file) {
+                Ok(contents) => contents,
+                Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+                Err(error) => return Err(error),
+            };
+            let key = format!("preset.{}", name);
+            fs::write(file, set_line(&contents, &key, &generator.to_preset()))?;
+        }
+        self.set_preset(name, generator);
+        Ok(())
+    }
+
+    /// Sets a preset in memory, replacing any preset with the same name
+    fn set_preset(&mut self, name: &str, generator: Generator) {
+        match self.presets.iter_mut().find(|(x, _)| x == name) {
+            Some(preset) => preset.1 = generator,
+            None => self.presets.push((name.to_string(), generator)),
+        }
+    }
+}
+
+/// Sets a key in the contents of a config file, rewriting the first line with the key and
+/// dropping any later ones, or appending a line if the key isn't there yet
+///
+/// # Arguments
+///
+/// * `contents` - The contents of the config file
+/// * `key`      - The key to set
+/// * `value`    - What to set it to
+///
+/// # Returns
+///
+/// The new contents of the config file
+fn set_line(contents: &str, key: &str, value: &str) -> String {
+    let line = format!("{} = {}", key, value);
+    let mut set = false;
+    let mut lines = vec![];
+
+    for current in contents.lines() {
+        let trimmed = current.trim();
+        let is_key = !trimmed.starts_with('#')
+            && trimmed
+                .split_once('=')
+                .is_some_and(|(x, _)| x.trim() == key);
+        match (is_key, set) {
+            (true, false) => {
+                lines.push(line.as_str());
+                set = true;
+            }
+            (true, true) => (),
+            (false, _) => lines.push(current),
+        }
+    }
+    if !set {
+        lines.push(&line);
+    }
+
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            file: None,
+            clipboard_timeout: Duration::from_secs(DEFAULT_CLIPBOARD_TIMEOUT),
+            clipboard_backend: None,
+            breach_file: None,
+            audit: AuditOptions::default(),
+            expiry_warning_days: DEFAULT_EXPIRY_WARNING_DAYS,
+            trash_retention_days: DEFAULT_TRASH_RETENTION_DAYS,
+            presets: vec![],
+        }
+    }
+}
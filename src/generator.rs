@@ -0,0 +1,426 @@
+//! A module that contains the logic for generating random passwords
+mod derive;
+mod passphrase;
+mod pronounceable;
+
+pub use derive::DerivationOptions;
+pub(crate) use passphrase::wordlist;
+pub use passphrase::PassphraseOptions;
+pub use pronounceable::PronounceableOptions;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+const DIGITS: &str = "0123456789";
+const DEFAULT_SYMBOLS: &str = "!#$%&()*+,-./:;<=>?@[]^_{|}~";
+/// Characters that are easily mistaken for one another
+const LOOKALIKES: &str = "0Oo1lI|";
+
+const DEFAULT_LENGTH: usize = 20;
+/// The longest password that can be generated, so a typo can't ask for an enormous one
+const MAX_LENGTH: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Enum of the different kinds of password that can be generated
+pub enum Generator {
+    /// Random characters
+    Characters(GeneratorOptions),
+    /// Random words
+    Passphrase(PassphraseOptions),
+    /// Random syllables
+    Pronounceable(PronounceableOptions),
+}
+
+impl Generator {
+    /// Generates a password with the generator's options
+    ///
+    /// # Returns
+    ///
+    /// Either the password, or an error explaining why the options can't produce one
+    pub fn generate(&self) -> Result<String, String> {
+        match self {
+            Generator::Characters(options) => options.generate(),
+            Generator::Passphrase(options) => options.generate(),
+            Generator::Pronounceable(options) => options.generate(),
+        }
+    }
+
+    /// Returns the entropy of the passwords the generator produces, in bits
+    pub fn entropy_bits(&self) -> f64 {
+        match self {
+            Generator::Characters(options) => options.entropy_bits(),
+            Generator::Passphrase(options) => options.entropy_bits(),
+            Generator::Pronounceable(options) => options.entropy_bits(),
+        }
+    }
+
+    /// Converts the generator into the single line format presets are saved in
+    pub fn to_preset(&self) -> String {
+        match self {
+            Generator::Characters(options) => options.to_preset(),
+            Generator::Passphrase(options) => options.to_preset(),
+            Generator::Pronounceable(options) => options.to_preset(),
+        }
+    }
+
+    /// Reads a generator from the format presets are saved in
+    ///
+    /// # Arguments
+    ///
+    /// * `preset` - The saved preset
+    ///
+    /// # Returns
+    ///
+    /// `None` if the preset is malformed, otherwise the generator
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::generator::{Generator, PassphraseOptions};
+    /// let generator = Generator::Passphrase(PassphraseOptions::default());
+    /// let preset = generator.to_preset();
+    /// assert_eq!(Generator::from_preset(&preset), Some(generator));
+    /// ```
+    pub fn from_preset(preset: &str) -> Option<Self> {
+        match preset.split(',').next() {
+            Some("passphrase") => PassphraseOptions::from_preset(preset).map(Generator::Passphrase),
+            Some("pronounceable") => {
+                PronounceableOptions::from_preset(preset).map(Generator::Pronounceable)
+            }
+            _ => GeneratorOptions::from_preset(preset).map(Generator::Characters),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A struct that stores the options used to generate a random password
+pub struct GeneratorOptions {
+    length: usize,
+    uppercase: bool,
+    lowercase: bool,
+    digits: bool,
+    symbols: bool,
+    symbol_set: String,
+    exclude_lookalikes: bool,
+    require_each_class: bool,
+}
+
+impl GeneratorOptions {
+    /// Returns the length of the generated passwords
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// Sets the length of the generated passwords, up to `MAX_LENGTH`
+    pub fn set_length(&mut self, length: usize) {
+        self.length = length.min(MAX_LENGTH);
+    }
+
+    /// Returns whether generated passwords can contain uppercase letters
+    pub fn uppercase(&self) -> bool {
+        self.uppercase
+    }
+
+    /// Sets whether generated passwords can contain uppercase letters
+    pub fn set_uppercase(&mut self, uppercase: bool) {
+        self.uppercase = uppercase;
+    }
+
+    /// Returns whether generated passwords can contain lowercase letters
+    pub fn lowercase(&self) -> bool {
+        self.lowercase
+    }
+
+    /// Sets whether generated passwords can contain lowercase letters
+    pub fn set_lowercase(&mut self, lowercase: bool) {
+        self.lowercase = lowercase;
+    }
+
+    /// Returns whether generated passwords can contain digits
+    pub fn digits(&self) -> bool {
+        self.digits
+    }
+
+    /// Sets whether generated passwords can contain digits
+    pub fn set_digits(&mut self, digits: bool) {
+        self.digits = digits;
+    }
+
+    /// Returns whether generated passwords can contain symbols
+    pub fn symbols(&self) -> bool {
+        self.symbols
+    }
+
+    /// Sets whether generated passwords can contain symbols
+    pub fn set_symbols(&mut self, symbols: bool) {
+        self.symbols = symbols;
+    }
+
+    /// Returns the symbols generated passwords can contain
+    pub fn symbol_set(&self) -> &str {
+        &self.symbol_set
+    }
+
+    /// Sets the symbols generated passwords can contain
+    pub fn set_symbol_set(&mut self, symbol_set: String) {
+        self.symbol_set = symbol_set;
+    }
+
+    /// Returns whether characters that look alike, such as 0 and O, are left out
+    pub fn exclude_lookalikes(&self) -> bool {
+        self.exclude_lookalikes
+    }
+
+    /// Sets whether characters that look alike, such as 0 and O, are left out
+    pub fn set_exclude_lookalikes(&mut self, exclude_lookalikes: bool) {
+        self.exclude_lookalikes = exclude_lookalikes;
+    }
+
+    /// Returns whether generated passwords contain at least one character of every enabled class
+    pub fn require_each_class(&self) -> bool {
+        self.require_each_class
+    }
+
+    /// Sets whether generated passwords contain at least one character of every enabled class
+    pub fn set_require_each_class(&mut self, require_each_class: bool) {
+        self.require_each_class = require_each_class;
+    }
+
+    /// Returns the characters of every enabled class, with look-alikes removed if they're
+    /// excluded
+    fn classes(&self) -> Vec<Vec<char>> {
+        [
+            (self.uppercase, UPPERCASE),
+            (self.lowercase, LOWERCASE),
+            (self.digits, DIGITS),
+            (self.symbols, self.symbol_set.as_str()),
+        ]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, chars)| {
+            let mut chars: Vec<char> = chars
+                .chars()
+                .filter(|c| !(self.exclude_lookalikes && LOOKALIKES.contains(*c)))
+                .collect();
+            chars.sort_unstable();
+            chars.dedup();
+            chars
+        })
+        .collect()
+    }
+
+    /// Generates a random password with the options
+    ///
+    /// # Returns
+    ///
+    /// Either the password, or an error explaining why the options can't produce one
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::generator::GeneratorOptions;
+    /// let mut options = GeneratorOptions::default();
+    /// options.set_length(12);
+    /// options.set_symbols(false);
+    ///
+    /// let password = options.generate().unwrap();
+    /// assert_eq!(password.chars().count(), 12);
+    /// assert!(password.chars().all(|c| c.is_ascii_alphanumeric()));
+    /// ```
+    pub fn generate(&self) -> Result<String, String> {
+        let classes = self.classes();
+
+        if classes.is_empty() {
+            return Err("At least one kind of character must be enabled".to_string());
+        }
+        if classes.iter().any(|class| class.is_empty()) {
+            return Err("The symbol set has no usable symbols".to_string());
+        }
+        if self.length == 0 {
+            return Err("The length must be at least 1".to_string());
+        }
+        if self.require_each_class && self.length < classes.len() {
+            return Err(format!(
+                "The length must be at least {} to fit one of each kind of character",
+                classes.len()
+            ));
+        }
+
+        let mut rng = rand::thread_rng();
+        let pool: Vec<char> = classes.concat();
+        let mut password: Vec<char> = vec![];
+
+        if self.require_each_class {
+            for class in &classes {
+                password.push(class[rng.gen_range(0..class.len())]);
+            }
+        }
+
+        while password.len() < self.length {
+            password.push(pool[rng.gen_range(0..pool.len())]);
+        }
+
+        password.shuffle(&mut rng);
+
+        Ok(password.into_iter().collect())
+    }
+
+    /// Returns the entropy of the passwords the options generate, in bits. This treats every
+    /// character as picked from all the enabled classes, so it slightly overestimates when one of
+    /// each class is required
+    pub fn entropy_bits(&self) -> f64 {
+        let pool: usize = self.classes().iter().map(|class| class.len()).sum();
+        match pool {
+            0 => 0.0,
+            pool => self.length as f64 * (pool as f64).log2(),
+        }
+    }
+
+    /// Converts the options into the single line format presets are saved in
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::generator::GeneratorOptions;
+    /// let mut options = GeneratorOptions::default();
+    /// options.set_length(12);
+    /// options.set_symbol_set("!,#".to_string());
+    ///
+    /// let preset = options.to_preset();
+    /// assert_eq!(GeneratorOptions::from_preset(&preset), Some(options));
+    /// ```
+    pub fn to_preset(&self) -> String {
+        let mut preset = vec![format!("length={}", self.length)];
+
+        for (enabled, flag) in [
+            (self.uppercase, "upper"),
+            (self.lowercase, "lower"),
+            (self.digits, "digits"),
+            (self.symbols, "symbols"),
+            (self.exclude_lookalikes, "no-lookalikes"),
+            (self.require_each_class, "each"),
+        ] {
+            if enabled {
+                preset.push(flag.to_string());
+            }
+        }
+
+        // The symbol set goes last as it can contain commas
+        preset.push(format!("set={}", self.symbol_set));
+
+        preset.join(",")
+    }
+
+    /// Reads options from the format presets are saved in
+    ///
+    /// # Arguments
+    ///
+    /// * `preset` - The saved preset
+    ///
+    /// # Returns
+    ///
+    /// `None` if the preset is malformed, otherwise the options
+    pub fn from_preset(preset: &str) -> Option<Self> {
+        let (flags, symbol_set) = preset.split_once(",set=")?;
+
+        let mut options = Self {
+            length: 0,
+            uppercase: false,
+            lowercase: false,
+            digits: false,
+            symbols: false,
+            symbol_set: symbol_set.to_string(),
+            exclude_lookalikes: false,
+            require_each_class: false,
+        };
+
+        for flag in flags.split(',') {
+            match flag.trim() {
+                "upper" => options.uppercase = true,
+                "lower" => options.lowercase = true,
+                "digits" => options.digits = true,
+                "symbols" => options.symbols = true,
+                "no-lookalikes" => options.exclude_lookalikes = true,
+                "each" => options.require_each_class = true,
+                flag => options.set_length(flag.strip_prefix("length=")?.parse().ok()?),
+            }
+        }
+
+        Some(options)
+    }
+}
+
+impl Default for GeneratorOptions {
+    /// Creates GeneratorOptions that produce 20 character passwords using every kind of
+    /// character, with at least one of each
+    fn default() -> Self {
+        Self {
+            length: DEFAULT_LENGTH,
+            uppercase: true,
+            lowercase: true,
+            digits: true,
+            symbols: true,
+            symbol_set: DEFAULT_SYMBOLS.to_string(),
+            exclude_lookalikes: false,
+            require_each_class: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GeneratorOptions;
+
+    #[test]
+    /// Tests that generated passwords respect the enabled classes, exclusions and guarantees
+    fn generate_respects_options() {
+        let mut options = GeneratorOptions::default();
+        options.set_length(4);
+        options.set_symbol_set("!".to_string());
+        options.set_exclude_lookalikes(true);
+
+        for _ in 0..100 {
+            let password = options.generate().unwrap();
+            assert_eq!(password.chars().count(), 4);
+            assert!(password.chars().any(|c| c.is_ascii_uppercase()));
+            assert!(password.chars().any(|c| c.is_ascii_lowercase()));
+            assert!(password.chars().any(|c| c.is_ascii_digit()));
+            assert!(password.contains('!'));
+            assert!(!password.contains(['0', 'O', 'o', '1', 'l', 'I']));
+        }
+    }
+
+    #[test]
+    /// Tests that options that can't produce a password are rejected
+    fn generate_rejects_impossible_options() {
+        let mut options = GeneratorOptions::default();
+        options.set_length(3);
+        assert!(options.generate().is_err());
+
+        options.set_require_each_class(false);
+        assert!(options.generate().is_ok());
+
+        options.set_symbol_set(String::new());
+        assert!(options.generate().is_err());
+
+        let mut options = GeneratorOptions::default();
+        options.set_uppercase(false);
+        options.set_lowercase(false);
+        options.set_digits(false);
+        options.set_symbols(false);
+        assert!(options.generate().is_err());
+    }
+
+    #[test]
+    /// Tests that lengths are capped, so a typo can't ask for an enormous password
+    fn length_is_clamped() {
+        let mut options = GeneratorOptions::default();
+        options.set_length(100_000_000);
+        assert_eq!(options.length(), super::MAX_LENGTH);
+
+        let preset = GeneratorOptions::from_preset("length=100000000,lower,set=").unwrap();
+        assert_eq!(preset.length(), super::MAX_LENGTH);
+    }
+}
@@ -0,0 +1,321 @@
+//! Stateless passwords, derived from the master password and details about the site instead of
+//! being stored
+//!
+//! Derivation follows LessPass: PBKDF2-SHA256 of the master password, salted with the site, login
+//! and counter, is used as a large number to pick characters from, so the same inputs always give
+//! the same password on any machine that implements it
+
+use hmac::Hmac;
+use sha2::Sha256;
+
+const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &str = "0123456789";
+const SYMBOLS: &str = "!\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~";
+
+const ITERATIONS: u32 = 100_000;
+const DEFAULT_LENGTH: usize = 16;
+/// LessPass' length limits, which keep every password within the entropy PBKDF2 produces
+const MIN_LENGTH: usize = 5;
+const MAX_LENGTH: usize = 35;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A struct that stores everything, apart from the master password, a password is derived from
+pub struct DerivationOptions {
+    site: String,
+    login: String,
+    counter: u32,
+    length: usize,
+    lowercase: bool,
+    uppercase: bool,
+    digits: bool,
+    symbols: bool,
+}
+
+impl DerivationOptions {
+    /// Creates DerivationOptions for the given site and login, using LessPass' defaults of a 16
+    /// character password with every kind of character and a counter of 1
+    ///
+    /// # Arguments
+    ///
+    /// * `site`  - The site the password is for
+    /// * `login` - The username or email used on the site
+    pub fn new<T: ToString, U: ToString>(site: T, login: U) -> Self {
+        Self {
+            site: site.to_string(),
+            login: login.to_string(),
+            counter: 1,
+            length: DEFAULT_LENGTH,
+            lowercase: true,
+            uppercase: true,
+            digits: true,
+            symbols: true,
+        }
+    }
+
+    /// Returns the site the password is for
+    pub fn site(&self) -> &str {
+        &self.site
+    }
+
+    /// Sets the site the password is for
+    pub fn set_site(&mut self, site: String) {
+        self.site = site;
+    }
+
+    /// Returns the username or email used on the site
+    pub fn login(&self) -> &str {
+        &self.login
+    }
+
+    /// Sets the username or email used on the site
+    pub fn set_login(&mut self, login: String) {
+        self.login = login;
+    }
+
+    /// Returns the counter, which is bumped to get a new password for the same site and login
+    pub fn counter(&self) -> u32 {
+        self.counter
+    }
+
+    /// Sets the counter
+    pub fn set_counter(&mut self, counter: u32) {
+        self.counter = counter;
+    }
+
+    /// Returns the length of the derived password
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// Sets the length of the derived password
+    pub fn set_length(&mut self, length: usize) {
+        self.length = length;
+    }
+
+    /// Returns whether the derived password contains lowercase letters
+    pub fn lowercase(&self) -> bool {
+        self.lowercase
+    }
+
+    /// Sets whether the derived password contains lowercase letters
+    pub fn set_lowercase(&mut self, lowercase: bool) {
+        self.lowercase = lowercase;
+    }
+
+    /// Returns whether the derived password contains uppercase letters
+    pub fn uppercase(&self) -> bool {
+        self.uppercase
+    }
+
+    /// Sets whether the derived password contains uppercase letters
+    pub fn set_uppercase(&mut self, uppercase: bool) {
+        self.uppercase = uppercase;
+    }
+
+    /// Returns whether the derived password contains digits
+    pub fn digits(&self) -> bool {
+        self.digits
+    }
+
+    /// Sets whether the derived password contains digits
+    pub fn set_digits(&mut self, digits: bool) {
+        self.digits = digits;
+    }
+
+    /// Returns whether the derived password contains symbols
+    pub fn symbols(&self) -> bool {
+        self.symbols
+    }
+
+    /// Sets whether the derived password contains symbols
+    pub fn set_symbols(&mut self, symbols: bool) {
+        self.symbols = symbols;
+    }
+
+    /// Returns the symbols derived passwords pick from. LessPass always uses all of them, so
+    /// unlike the generator they can't be narrowed down
+    pub fn symbol_set(&self) -> &'static str {
+        SYMBOLS
+    }
+
+    /// Returns the lengths a password can be derived with
+    pub fn length_range(&self) -> std::ops::RangeInclusive<usize> {
+        MIN_LENGTH..=MAX_LENGTH
+    }
+
+    /// Returns the characters of every enabled class, in the order LessPass uses them
+    fn rules(&self) -> Vec<&'static str> {
+        [
+            (self.lowercase, LOWERCASE),
+            (self.uppercase, UPPERCASE),
+            (self.digits, DIGITS),
+            (self.symbols, SYMBOLS),
+        ]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, chars)| chars)
+        .collect()
+    }
+
+    /// Derives the password from the master password
+    ///
+    /// # Arguments
+    ///
+    /// * `master` - The master password
+    ///
+    /// # Returns
+    ///
+    /// Either the password, or an error explaining why the options can't produce one
+    pub fn derive(&self, master: &str) -> Result<String, String> {
+        let rules = self.rules();
+
+        if rules.is_empty() {
+            return Err("At least one kind of character must be enabled".to_string());
+        }
+        if !(MIN_LENGTH..=MAX_LENGTH).contains(&self.length) {
+            return Err(format!(
+                "The length must be between {} and {}",
+                MIN_LENGTH, MAX_LENGTH
+            ));
+        }
+
+        let salt = format!("{}{}{:x}", self.site, self.login, self.counter);
+        let mut key = [0u8; 32];
+        pbkdf2::pbkdf2::<Hmac<Sha256>>(master.as_bytes(), salt.as_bytes(), ITERATIONS, &mut key);
+        let mut entropy = BigNumber::from_bytes(&key);
+
+        let chars: Vec<char> = rules.concat().chars().collect();
+        let mut password: Vec<char> = (0..self.length - rules.len())
+            .map(|_| chars[entropy.div_rem(chars.len() as u32) as usize])
+            .collect();
+
+        // One character of each class is picked, then each is inserted at a position also picked
+        // from the entropy
+        let required: Vec<char> = rules
+            .iter()
+            .map(|rule| {
+                let rule: Vec<char> = rule.chars().collect();
+                rule[entropy.div_rem(rule.len() as u32) as usize]
+            })
+            .collect();
+        for c in required {
+            let position = entropy.div_rem(password.len() as u32) as usize;
+            password.insert(position, c);
+        }
+
+        Ok(password.into_iter().collect())
+    }
+
+    /// Converts the length and character classes into the single line format they're saved in
+    pub fn to_rules(&self) -> String {
+        let mut rules = vec![format!("length={}", self.length)];
+
+        for (enabled, flag) in [
+            (self.lowercase, "lower"),
+            (self.uppercase, "upper"),
+            (self.digits, "digits"),
+            (self.symbols, "symbols"),
+        ] {
+            if enabled {
+                rules.push(flag.to_string());
+            }
+        }
+
+        rules.join(",")
+    }
+
+    /// Reads the length and character classes from the format they're saved in
+    ///
+    /// # Arguments
+    ///
+    /// * `rules` - The saved rules
+    ///
+    /// # Returns
+    ///
+    /// `None` if the rules are malformed, otherwise the options with the rules set
+    pub fn with_rules(&self, rules: &str) -> Option<Self> {
+        let mut options = Self {
+            length: 0,
+            lowercase: false,
+            uppercase: false,
+            digits: false,
+            symbols: false,
+            ..self.clone()
+        };
+
+        for flag in rules.split(',') {
+            match flag.trim() {
+                "lower" => options.lowercase = true,
+                "upper" => options.uppercase = true,
+                "digits" => options.digits = true,
+                "symbols" => options.symbols = true,
+                flag => options.length = flag.strip_prefix("length=")?.parse().ok()?,
+            }
+        }
+
+        Some(options)
+    }
+}
+
+/// A big-endian unsigned integer, just big enough to pick characters from PBKDF2's output
+struct BigNumber(Vec<u32>);
+
+impl BigNumber {
+    /// Reads the number from big-endian bytes
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self(
+            bytes
+                .chunks(4)
+                .map(|chunk| chunk.iter().fold(0, |x, byte| (x << 8) | *byte as u32))
+                .collect(),
+        )
+    }
+
+    /// Divides the number by the divisor in place
+    ///
+    /// # Returns
+    ///
+    /// The remainder
+    fn div_rem(&mut self, divisor: u32) -> u32 {
+        let mut remainder = 0u64;
+        for digit in self.0.iter_mut() {
+            let current = (remainder << 32) | *digit as u64;
+            *digit = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+        remainder as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DerivationOptions;
+
+    #[test]
+    /// Tests that passwords match the ones LessPass derives for the same inputs
+    fn derive_matches_lesspass() {
+        let options = DerivationOptions::new("example.org", "contact@example.org");
+        assert_eq!(options.derive("password").unwrap(), "WHLpUL)e00[iHR+w");
+
+        let mut options = options;
+        options.set_counter(2);
+        options.set_symbols(false);
+        options.set_length(14);
+        let password = options.derive("password").unwrap();
+        assert_eq!(password.len(), 14);
+        assert!(password.chars().all(|c| c.is_ascii_alphanumeric()));
+    }
+
+    #[test]
+    /// Tests that the rules survive being saved
+    fn rules_round_trip() {
+        let mut options = DerivationOptions::new("site", "login");
+        options.set_length(20);
+        options.set_digits(false);
+
+        let rules = options.to_rules();
+        let base = DerivationOptions::new("site", "login");
+        assert_eq!(base.with_rules(&rules), Some(options));
+    }
+}
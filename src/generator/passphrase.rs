@@ -0,0 +1,264 @@
+//! Diceware style passphrases, made of words picked at random from an embedded wordlist
+//!
+//! The embedded wordlist is the BIP-39 English list, which has 2048 short, common and distinct
+//! words. Entropy is worked out from the size of the list, so it can be swapped for any other list
+//! of unique words, one per line. Lines can also start with the dice rolls that pick the word, so
+//! lists in the EFF's format, like its long list, can be embedded exactly as they're published
+
+use rand::Rng;
+use std::sync::OnceLock;
+
+const WORDLIST: &str = include_str!("wordlist.txt");
+
+const DEFAULT_WORDS: usize = 6;
+/// The most words a passphrase can have, so a typo can't ask for an enormous one
+const MAX_WORDS: usize = 64;
+const DEFAULT_SEPARATOR: &str = "-";
+
+/// Returns the words in the embedded wordlist, which is only read the first time it's needed
+pub(crate) fn wordlist() -> &'static [&'static str] {
+    static WORDS: OnceLock<Vec<&'static str>> = OnceLock::new();
+    WORDS.get_or_init(|| parse_wordlist(WORDLIST))
+}
+
+/// Reads the words out of a wordlist, which has either one word per line or the dice rolls that
+/// pick the word then the word
+///
+/// # Arguments
+///
+/// * `contents` - The contents of the wordlist
+fn parse_wordlist(contents: &str) -> Vec<&str> {
+    contents
+        .lines()
+        .filter_map(|line| line.split_whitespace().last())
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A struct that stores the options used to generate a passphrase
+pub struct PassphraseOptions {
+    words: usize,
+    separator: String,
+    capitalise: bool,
+    include_digit: bool,
+}
+
+impl PassphraseOptions {
+    /// Returns how many words are in generated passphrases
+    pub fn words(&self) -> usize {
+        self.words
+    }
+
+    /// Sets how many words are in generated passphrases, up to `MAX_WORDS`
+    pub fn set_words(&mut self, words: usize) {
+        self.words = words.min(MAX_WORDS);
+    }
+
+    /// Returns what goes between the words
+    pub fn separator(&self) -> &str {
+        &self.separator
+    }
+
+    /// Sets what goes between the words
+    pub fn set_separator(&mut self, separator: String) {
+        self.separator = separator;
+    }
+
+    /// Returns whether the first letter of every word is capitalised
+    pub fn capitalise(&self) -> bool {
+        self.capitalise
+    }
+
+    /// Sets whether the first letter of every word is capitalised
+    pub fn set_capitalise(&mut self, capitalise: bool) {
+        self.capitalise = capitalise;
+    }
+
+    /// Returns whether a random digit is added to the end of a random word
+    pub fn include_digit(&self) -> bool {
+        self.include_digit
+    }
+
+    /// Sets whether a random digit is added to the end of a random word
+    pub fn set_include_digit(&mut self, include_digit: bool) {
+        self.include_digit = include_digit;
+    }
+
+    /// Generates a random passphrase with the options
+    ///
+    /// # Returns
+    ///
+    /// Either the passphrase, or an error explaining why the options can't produce one
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::generator::PassphraseOptions;
+    /// let mut options = PassphraseOptions::default();
+    /// options.set_words(4);
+    /// options.set_separator(" ".to_string());
+    ///
+    /// let passphrase = options.generate().unwrap();
+    /// assert_eq!(passphrase.split(' ').count(), 4);
+    /// ```
+    pub fn generate(&self) -> Result<String, String> {
+        if self.words == 0 {
+            return Err("The passphrase must have at least 1 word".to_string());
+        }
+
+        let wordlist = wordlist();
+        let mut rng = rand::thread_rng();
+
+        let mut words: Vec<String> = (0..self.words)
+            .map(|_| {
+                let word = wordlist[rng.gen_range(0..wordlist.len())];
+                match self.capitalise {
+                    true => capitalise(word),
+                    false => word.to_string(),
+                }
+            })
+            .collect();
+
+        if self.include_digit {
+            let word = rng.gen_range(0..words.len());
+            words[word].push_str(&rng.gen_range(0..10).to_string());
+        }
+
+        Ok(words.join(&self.separator))
+    }
+
+    /// Returns the entropy of the passphrases the options generate, in bits
+    pub fn entropy_bits(&self) -> f64 {
+        let mut bits = self.words as f64 * (wordlist().len() as f64).log2();
+        if self.include_digit && self.words > 0 {
+            bits += 10f64.log2() + (self.words as f64).log2();
+        }
+        bits
+    }
+
+    /// Converts the options into the single line format presets are saved in
+    pub fn to_preset(&self) -> String {
+        let mut preset = vec!["passphrase".to_string(), format!("words={}", self.words)];
+
+        if self.capitalise {
+            preset.push("capitalise".to_string());
+        }
+        if self.include_digit {
+            preset.push("digit".to_string());
+        }
+
+        // The separator goes last as it can contain commas
+        preset.push(format!("separator={}", self.separator));
+
+        preset.join(",")
+    }
+
+    /// Reads options from the format presets are saved in
+    ///
+    /// # Arguments
+    ///
+    /// * `preset` - The saved preset
+    ///
+    /// # Returns
+    ///
+    /// `None` if the preset is malformed or isn't for a passphrase, otherwise the options
+    pub fn from_preset(preset: &str) -> Option<Self> {
+        let (flags, separator) = preset.split_once(",separator=")?;
+        let mut flags = flags.split(',');
+
+        if flags.next()? != "passphrase" {
+            return None;
+        }
+
+        let mut options = Self {
+            words: 0,
+            separator: separator.to_string(),
+            capitalise: false,
+            include_digit: false,
+        };
+
+        for flag in flags {
+            match flag.trim() {
+                "capitalise" => options.capitalise = true,
+                "digit" => options.include_digit = true,
+                flag => options.set_words(flag.strip_prefix("words=")?.parse().ok()?),
+            }
+        }
+
+        Some(options)
+    }
+}
+
+impl Default for PassphraseOptions {
+    /// Creates PassphraseOptions that produce six lowercase words separated by dashes
+    fn default() -> Self {
+        Self {
+            words: DEFAULT_WORDS,
+            separator: DEFAULT_SEPARATOR.to_string(),
+            capitalise: false,
+            include_digit: false,
+        }
+    }
+}
+
+/// Returns the word with its first letter in uppercase
+fn capitalise(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_wordlist, wordlist, PassphraseOptions};
+
+    #[test]
+    /// Tests that passphrases are made of words from the wordlist with the options applied
+    fn generate_respects_options() {
+        let mut options = PassphraseOptions::default();
+        options.set_words(5);
+        options.set_separator(".".to_string());
+        options.set_capitalise(true);
+        options.set_include_digit(true);
+
+        let wordlist = wordlist();
+        let passphrase = options.generate().unwrap();
+        let words: Vec<&str> = passphrase.split('.').collect();
+
+        assert_eq!(words.len(), 5);
+        assert_eq!(passphrase.chars().filter(|c| c.is_ascii_digit()).count(), 1);
+        for word in words {
+            assert!(word.starts_with(|c: char| c.is_uppercase()));
+            let word = word.trim_end_matches(|c: char| c.is_ascii_digit());
+            assert!(wordlist.contains(&word.to_lowercase().as_str()));
+        }
+    }
+
+    #[test]
+    /// Tests that the entropy grows with the number of words and the size of the wordlist
+    fn entropy_bits_works() {
+        let bits_per_word = (wordlist().len() as f64).log2();
+
+        let mut options = PassphraseOptions::default();
+        options.set_words(4);
+        assert_eq!(options.entropy_bits(), 4.0 * bits_per_word);
+
+        options.set_include_digit(true);
+        assert!(options.entropy_bits() > 4.0 * bits_per_word);
+    }
+
+    #[test]
+    /// Tests that wordlists with dice rolls before each word, like the EFF's, are read
+    fn parse_wordlist_reads_dice_rolls() {
+        assert_eq!(
+            parse_wordlist("abandon\nability\n"),
+            vec!["abandon", "ability"]
+        );
+        assert_eq!(
+            parse_wordlist("11111\tabacus\n11112\tabdomen\n\n"),
+            vec!["abacus", "abdomen"]
+        );
+    }
+}
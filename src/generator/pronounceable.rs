@@ -0,0 +1,271 @@
+//! Pronounceable passwords, made of consonant-vowel syllables so they can be read out loud
+//!
+//! Only consonants whose names can't be misheard over the phone are used. b, d, p, t, v, z, c and
+//! g rhyme with each other and with e, j and k rhyme with a, y sounds like i, and only one of each
+//! of the pairs m/n and f/s is kept, so every syllable can be spelt back without asking. With so
+//! few consonants each syllable carries less entropy, so passwords have more syllables
+
+use rand::Rng;
+
+const CONSONANTS: &str = "hlmrsw";
+const VOWELS: &str = "aeiou";
+/// Symbols with short, unambiguous spoken names
+const SYMBOLS: &str = "!#$%&*+-=?@";
+
+const DEFAULT_SYLLABLES: usize = 10;
+/// The most syllables a password can have, so a typo can't ask for an enormous one
+const MAX_SYLLABLES: usize = 128;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A struct that stores the options used to generate a pronounceable password
+pub struct PronounceableOptions {
+    syllables: usize,
+    capitalise: bool,
+    include_digit: bool,
+    include_symbol: bool,
+}
+
+impl PronounceableOptions {
+    /// Returns how many syllables are in generated passwords
+    pub fn syllables(&self) -> usize {
+        self.syllables
+    }
+
+    /// Sets how many syllables are in generated passwords, up to `MAX_SYLLABLES`
+    pub fn set_syllables(&mut self, syllables: usize) {
+        self.syllables = syllables.min(MAX_SYLLABLES);
+    }
+
+    /// Returns whether the first letter of the password is capitalised
+    pub fn capitalise(&self) -> bool {
+        self.capitalise
+    }
+
+    /// Sets whether the first letter of the password is capitalised
+    pub fn set_capitalise(&mut self, capitalise: bool) {
+        self.capitalise = capitalise;
+    }
+
+    /// Returns whether a random digit is inserted between two syllables
+    pub fn include_digit(&self) -> bool {
+        self.include_digit
+    }
+
+    /// Sets whether a random digit is inserted between two syllables
+    pub fn set_include_digit(&mut self, include_digit: bool) {
+        self.include_digit = include_digit;
+    }
+
+    /// Returns whether a random symbol is inserted between two syllables
+    pub fn include_symbol(&self) -> bool {
+        self.include_symbol
+    }
+
+    /// Sets whether a random symbol is inserted between two syllables
+    pub fn set_include_symbol(&mut self, include_symbol: bool) {
+        self.include_symbol = include_symbol;
+    }
+
+    /// Generates a random pronounceable password with the options
+    ///
+    /// # Returns
+    ///
+    /// Either the password, or an error explaining why the options can't produce one
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::generator::PronounceableOptions;
+    /// let mut options = PronounceableOptions::default();
+    /// options.set_syllables(4);
+    /// options.set_include_digit(false);
+    ///
+    /// let password = options.generate().unwrap();
+    /// assert_eq!(password.len(), 8);
+    /// ```
+    pub fn generate(&self) -> Result<String, String> {
+        if self.syllables == 0 {
+            return Err("The password must have at least 1 syllable".to_string());
+        }
+
+        let mut rng = rand::thread_rng();
+        let mut pick = |chars: &str| {
+            let chars: Vec<char> = chars.chars().collect();
+            chars[rng.gen_range(0..chars.len())].to_string()
+        };
+
+        let mut parts: Vec<String> = (0..self.syllables)
+            .map(|_| pick(CONSONANTS) + &pick(VOWELS))
+            .collect();
+
+        if self.capitalise {
+            parts[0] = parts[0].to_uppercase()[..1].to_string() + &parts[0][1..];
+        }
+
+        // Extra characters go between syllables, or at either end, so the syllables stay whole
+        let mut extras = vec![];
+        if self.include_digit {
+            extras.push(pick("0123456789"));
+        }
+        if self.include_symbol {
+            extras.push(pick(SYMBOLS));
+        }
+        for extra in extras {
+            let boundary = rand::thread_rng().gen_range(0..=parts.len());
+            parts.insert(boundary, extra);
+        }
+
+        Ok(parts.concat())
+    }
+
+    /// Returns the entropy of the passwords the options generate, in bits
+    pub fn entropy_bits(&self) -> f64 {
+        let syllable = (CONSONANTS.len() * VOWELS.len()) as f64;
+        let mut bits = self.syllables as f64 * syllable.log2();
+        let mut parts = self.syllables as f64;
+
+        for (enabled, set) in [
+            (self.include_digit, 10),
+            (self.include_symbol, SYMBOLS.len()),
+        ] {
+            if enabled && self.syllables > 0 {
+                bits += (set as f64).log2() + (parts + 1.0).log2();
+                parts += 1.0;
+            }
+        }
+        bits
+    }
+
+    /// Converts the options into the single line format presets are saved in
+    pub fn to_preset(&self) -> String {
+        let mut preset = vec![
+            "pronounceable".to_string(),
+            format!("syllables={}", self.syllables),
+        ];
+
+        for (enabled, flag) in [
+            (self.capitalise, "capitalise"),
+            (self.include_digit, "digit"),
+            (self.include_symbol, "symbol"),
+        ] {
+            if enabled {
+                preset.push(flag.to_string());
+            }
+        }
+
+        preset.join(",")
+    }
+
+    /// Reads options from the format presets are saved in
+    ///
+    /// # Arguments
+    ///
+    /// * `preset` - The saved preset
+    ///
+    /// # Returns
+    ///
+    /// `None` if the preset is malformed or isn't for a pronounceable password, otherwise the
+    /// options
+    pub fn from_preset(preset: &str) -> Option<Self> {
+        let mut flags = preset.split(',');
+
+        if flags.next()? != "pronounceable" {
+            return None;
+        }
+
+        let mut options = Self {
+            syllables: 0,
+            capitalise: false,
+            include_digit: false,
+            include_symbol: false,
+        };
+
+        for flag in flags {
+            match flag.trim() {
+                "capitalise" => options.capitalise = true,
+                "digit" => options.include_digit = true,
+                "symbol" => options.include_symbol = true,
+                flag => options.set_syllables(flag.strip_prefix("syllables=")?.parse().ok()?),
+            }
+        }
+
+        Some(options)
+    }
+}
+
+impl Default for PronounceableOptions {
+    /// Creates PronounceableOptions that produce ten syllable passwords with a capital letter
+    /// and a digit
+    fn default() -> Self {
+        Self {
+            syllables: DEFAULT_SYLLABLES,
+            capitalise: true,
+            include_digit: true,
+            include_symbol: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PronounceableOptions, CONSONANTS, SYMBOLS, VOWELS};
+
+    #[test]
+    /// Tests that passwords alternate consonants and vowels, with extras between syllables
+    fn generate_respects_options() {
+        let mut options = PronounceableOptions::default();
+        options.set_syllables(3);
+        options.set_include_symbol(true);
+
+        for _ in 0..100 {
+            let password = options.generate().unwrap();
+            assert!(password.starts_with(|c: char| c.is_ascii_uppercase() || !c.is_alphabetic()));
+
+            let letters: Vec<char> = password
+                .to_lowercase()
+                .chars()
+                .filter(|c| c.is_ascii_alphabetic())
+                .collect();
+            assert_eq!(letters.len(), 6);
+            for syllable in letters.chunks(2) {
+                assert!(CONSONANTS.contains(syllable[0]));
+                assert!(VOWELS.contains(syllable[1]));
+            }
+
+            let extras: Vec<char> = password
+                .chars()
+                .filter(|c| !c.is_ascii_alphabetic())
+                .collect();
+            assert_eq!(extras.len(), 2);
+            assert!(extras.iter().any(|c| c.is_ascii_digit()));
+            assert!(extras.iter().any(|c| SYMBOLS.contains(*c)));
+        }
+    }
+
+    #[test]
+    /// Tests that each syllable adds the entropy of a consonant and a vowel, and that the extra
+    /// default syllables make up for the small set of consonants
+    fn entropy_bits_works() {
+        let mut options = PronounceableOptions::default();
+        options.set_include_digit(false);
+        options.set_syllables(4);
+        assert_eq!(options.entropy_bits(), 4.0 * 30f64.log2());
+
+        assert!(PronounceableOptions::default().entropy_bits() > 55.0);
+    }
+
+    #[test]
+    /// Tests that presets round trip
+    fn preset_round_trips() {
+        let mut options = PronounceableOptions::default();
+        options.set_syllables(5);
+        options.set_include_symbol(true);
+
+        let preset = options.to_preset();
+        assert_eq!(PronounceableOptions::from_preset(&preset), Some(options));
+        assert_eq!(
+            PronounceableOptions::from_preset("passphrase,words=4"),
+            None
+        );
+    }
+}
@@ -1,4 +1,14 @@
 pub mod account;
 pub mod app;
+pub mod audit;
+pub mod breach;
+pub mod clipboard;
+pub mod config;
+pub mod generator;
+pub mod policy;
+pub mod rotation;
 pub mod serialisation;
+pub mod strength;
 mod terminal_drawing;
+pub mod time;
+pub mod trash;
@@ -1,21 +1,91 @@
 use colored::Colorize;
+use password_manager::audit::{Audit, Report};
+use password_manager::breach::BreachList;
+use password_manager::clipboard::Clipboard;
+use password_manager::config::Config;
 use password_manager::serialisation::serialise;
+use password_manager::time;
+use password_manager::trash::split_trash;
 
 const PASSWORD_FILE: &str = "testing.txt";
+const CONFIG_FILE: &str = "config.txt";
+const USAGE: &str = "Usage: password_manager [audit <report.html|report.csv>]";
 
 fn main() {
-    let (accounts, password) = match password_manager::app::setup(PASSWORD_FILE) {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match args.as_slice() {
+        [] => run(),
+        [command, path] if command == "audit" => export_audit(path),
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    }
+}
+
+/// Runs the password manager, saving the vault when it's closed
+fn run() {
+    let config = Config::load(CONFIG_FILE);
+
+    let (accounts, password) = match password_manager::app::setup(PASSWORD_FILE, &config) {
         Some(config) => config,
         None => return,
     };
 
-    match password_manager::app::run(accounts, password) {
-        Ok((accounts, password)) => {
-            serialise(accounts, PASSWORD_FILE, password.trim()).expect("Failed to serialise data")
+    let mut clipboard = Clipboard::new(config.clipboard_timeout(), config.clipboard_backend());
+    let result =
+        password_manager::app::run(accounts, password, PASSWORD_FILE, config, &mut clipboard);
+
+    // The vault is saved before waiting for the clipboard to clear, so closing the terminal while
+    // it counts down doesn't lose the session's changes
+    let result = result.and_then(|(accounts, password)| {
+        serialise(accounts, PASSWORD_FILE, password.trim()).expect("Failed to serialise data");
+        password_manager::app::wait_for_clipboard(&mut clipboard)
+    });
+    if let Err(e) = result {
+        eprintln!("{}", " ERROR ".bright_white().on_bright_red());
+        eprintln!("{}", e);
+    }
+}
+
+/// Audits the vault and saves a report of it without opening the password manager
+///
+/// # Arguments
+///
+/// * `path` - Where to save the report. The extension picks the format
+fn export_audit(path: &str) {
+    // Unlocking offers to create a missing vault, which there's no point in when exporting
+    if !std::path::Path::new(PASSWORD_FILE).exists() {
+        eprintln!(
+            "{} There's no password file to audit at {}",
+            " ERROR ".bright_white().on_bright_red(),
+            PASSWORD_FILE
+        );
+        std::process::exit(1);
+    }
+
+    let (accounts, password) = match password_manager::app::unlock(PASSWORD_FILE) {
+        Some(config) => config,
+        None => std::process::exit(1),
+    };
+    let (accounts, _) = split_trash(accounts);
+    let config = Config::load(CONFIG_FILE);
+
+    let mut audit = Audit::run(&accounts, &password, &config.audit_options(), time::now());
+    if let Some(breach_file) = config.breach_file() {
+        let checked = BreachList::open(breach_file)
+            .and_then(|list| audit.check_breaches(&accounts, &password, &list));
+        if let Err(error) = checked {
+            eprintln!("\r{} {}", " WARNING ".black().on_yellow(), error);
         }
-        Err(e) => {
-            eprintln!("{}", " ERROR ".bright_white().on_bright_red());
-            eprintln!("{}", e);
+    }
+
+    match Report::new(&audit, &accounts, time::now()).save(path) {
+        Ok(()) => println!("\rSaved the audit report to {}", path),
+        Err(error) => {
+            eprintln!("\r{} {}", " ERROR ".bright_white().on_bright_red(), error);
+            std::process::exit(1);
         }
     }
 }
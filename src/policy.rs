@@ -0,0 +1,404 @@
+//! A module that contains the password rules sites enforce, so generated and edited passwords can
+//! be checked against them
+//!
+//! # Format
+//!
+//! Policies are saved on a single line of comma separated rules, such as
+//! `min=8,max=20,upper,digits,forbid=1234/password,allowed=!@#`. `upper`, `lower`, `digits` and
+//! `symbols` are the classes a password must contain, `forbid` lists sequences a password can't
+//! contain separated by `/`, and `allowed` lists the only symbols a site accepts. `allowed` goes
+//! last as it can contain commas
+
+use crate::generator::{DerivationOptions, GeneratorOptions};
+
+/// How many times the generator is retried before giving up on avoiding forbidden sequences
+const MAX_ATTEMPTS: usize = 100;
+
+/// The policies of some well-known sites, taken from their sign up pages. Sites change their
+/// rules, so these are only a starting point
+const KNOWN_POLICIES: [(&str, &str); 6] = [
+    ("amazon.com", "min=6,max=128"),
+    ("apple.com", "min=8,max=32,upper,lower,digits"),
+    (
+        "bankofamerica.com",
+        "min=8,max=20,lower,digits,allowed=@#*()+={}/?~;,.-_",
+    ),
+    ("google.com", "min=8,max=100"),
+    ("microsoft.com", "min=8,max=256"),
+    ("paypal.com", "min=8,max=20,digits"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+/// A struct that stores the rules a site's passwords must follow
+pub struct Policy {
+    min_length: usize,
+    max_length: Option<usize>,
+    uppercase: bool,
+    lowercase: bool,
+    digits: bool,
+    symbols: bool,
+    allowed_symbols: Option<String>,
+    forbidden: Vec<String>,
+}
+
+impl Policy {
+    /// Looks up the policy of a well-known site
+    ///
+    /// # Arguments
+    ///
+    /// * `site` - The site's domain or name, such as `paypal.com` or `PayPal`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::policy::Policy;
+    /// let policy = Policy::known("www.PayPal.com").unwrap();
+    /// assert!(policy.check("short1").is_err());
+    /// assert!(policy.check("long enough 1").is_ok());
+    /// ```
+    pub fn known(site: &str) -> Option<Self> {
+        let site = site.trim().to_lowercase();
+        let site = site.strip_prefix("www.").unwrap_or(&site);
+
+        KNOWN_POLICIES
+            .iter()
+            .find(|(domain, _)| *domain == site || domain.split('.').next() == Some(site))
+            .and_then(|(_, rules)| Self::from_rules(rules))
+    }
+
+    /// Returns the shortest length the site accepts
+    pub fn min_length(&self) -> usize {
+        self.min_length
+    }
+
+    /// Returns the longest length the site accepts, or `None` if there's no limit
+    pub fn max_length(&self) -> Option<usize> {
+        self.max_length
+    }
+
+    /// Checks whether the password follows the policy
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password to check
+    ///
+    /// # Returns
+    ///
+    /// An error describing the first rule the password breaks, if it breaks any
+    pub fn check(&self, password: &str) -> Result<(), String> {
+        let length = password.chars().count();
+        if length < self.min_length {
+            return Err(format!("must be at least {} characters", self.min_length));
+        }
+        if let Some(max_length) = self.max_length.filter(|max| length > *max) {
+            return Err(format!("must be at most {} characters", max_length));
+        }
+
+        for (required, name, matches) in [
+            (
+                self.uppercase,
+                "an uppercase letter",
+                char::is_uppercase as fn(char) -> bool,
+            ),
+            (self.lowercase, "a lowercase letter", char::is_lowercase),
+            (self.digits, "a digit", |c: char| c.is_ascii_digit()),
+            (self.symbols, "a symbol", is_symbol),
+        ] {
+            if required && !password.chars().any(matches) {
+                return Err(format!("must contain {}", name));
+            }
+        }
+
+        if let Some(allowed) = &self.allowed_symbols {
+            if let Some(c) = password
+                .chars()
+                .find(|c| is_symbol(*c) && !allowed.contains(*c))
+            {
+                return Err(format!("can't contain '{}'", c));
+            }
+        }
+
+        let lowercase = password.to_lowercase();
+        if let Some(sequence) = self
+            .forbidden
+            .iter()
+            .find(|sequence| lowercase.contains(&sequence.to_lowercase()))
+        {
+            return Err(format!("can't contain \"{}\"", sequence));
+        }
+
+        Ok(())
+    }
+
+    /// Changes generator options so that they only produce passwords of the right length and
+    /// character classes
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The options to change
+    pub fn constrain(&self, options: &mut GeneratorOptions) {
+        let mut length = options.length().max(self.min_length);
+        if let Some(max_length) = self.max_length {
+            length = length.min(max_length);
+        }
+        options.set_length(length);
+
+        if self.uppercase {
+            options.set_uppercase(true);
+        }
+        if self.lowercase {
+            options.set_lowercase(true);
+        }
+        if self.digits {
+            options.set_digits(true);
+        }
+        if self.symbols {
+            options.set_symbols(true);
+        }
+        if self.uppercase || self.lowercase || self.digits || self.symbols {
+            options.set_require_each_class(true);
+        }
+
+        if let Some(allowed) = &self.allowed_symbols {
+            let symbol_set: String = options
+                .symbol_set()
+                .chars()
+                .filter(|c| allowed.contains(*c))
+                .collect();
+            match (symbol_set.is_empty(), allowed.is_empty()) {
+                // The site doesn't accept any symbols at all
+                (true, true) => options.set_symbols(false),
+                (true, false) => options.set_symbol_set(allowed.clone()),
+                (false, _) => options.set_symbol_set(symbol_set),
+            }
+        }
+    }
+
+    /// Changes derivation options so that they derive passwords of the right length and
+    /// character classes. Derived passwords can't be retried, so they should still be checked
+    /// against the policy before they're used
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The options to change
+    pub fn constrain_derivation(&self, options: &mut DerivationOptions) {
+        let mut length = options.length().max(self.min_length);
+        if let Some(max_length) = self.max_length {
+            length = length.min(max_length);
+        }
+        let range = options.length_range();
+        options.set_length(length.clamp(*range.start(), *range.end()));
+
+        if self.uppercase {
+            options.set_uppercase(true);
+        }
+        if self.lowercase {
+            options.set_lowercase(true);
+        }
+        if self.digits {
+            options.set_digits(true);
+        }
+        if self.symbols {
+            options.set_symbols(true);
+        }
+
+        // Derived passwords pick from every symbol, so they can only have symbols if the site
+        // accepts all of them
+        if let Some(allowed) = &self.allowed_symbols {
+            if !options.symbol_set().chars().all(|c| allowed.contains(c)) {
+                options.set_symbols(false);
+            }
+        }
+    }
+
+    /// Calls the generator until it produces a password that follows the policy
+    ///
+    /// # Arguments
+    ///
+    /// * `generate` - Produces a password, or an error explaining why it can't
+    ///
+    /// # Returns
+    ///
+    /// Either a password that follows the policy, or why one couldn't be produced
+    pub fn retry<F>(&self, mut generate: F) -> Result<String, String>
+    where
+        F: FnMut() -> Result<String, String>,
+    {
+        let mut error = String::new();
+        for _ in 0..MAX_ATTEMPTS {
+            let password = generate()?;
+            match self.check(&password) {
+                Ok(()) => return Ok(password),
+                Err(e) => error = e,
+            }
+        }
+        Err(format!("The site's policy says the password {}", error))
+    }
+
+    /// Converts the policy into the single line format it's saved in
+    pub fn to_rules(&self) -> String {
+        let mut rules = vec![format!("min={}", self.min_length)];
+
+        if let Some(max_length) = self.max_length {
+            rules.push(format!("max={}", max_length));
+        }
+        for (required, flag) in [
+            (self.uppercase, "upper"),
+            (self.lowercase, "lower"),
+            (self.digits, "digits"),
+            (self.symbols, "symbols"),
+        ] {
+            if required {
+                rules.push(flag.to_string());
+            }
+        }
+        if !self.forbidden.is_empty() {
+            rules.push(format!("forbid={}", self.forbidden.join("/")));
+        }
+        if let Some(allowed) = &self.allowed_symbols {
+            rules.push(format!("allowed={}", allowed));
+        }
+
+        rules.join(",")
+    }
+
+    /// Reads a policy from the format it's saved in
+    ///
+    /// # Arguments
+    ///
+    /// * `rules` - The saved rules
+    ///
+    /// # Returns
+    ///
+    /// `None` if the rules are malformed, otherwise the policy
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::policy::Policy;
+    /// let policy = Policy::from_rules("min=4,symbols,forbid=abc,allowed=!,").unwrap();
+    /// assert_eq!(Policy::from_rules(&policy.to_rules()), Some(policy.clone()));
+    /// assert!(policy.check("xabcx!").is_err());
+    /// assert!(policy.check("xyz?").is_err());
+    /// assert!(policy.check("xyz,").is_ok());
+    /// ```
+    pub fn from_rules(rules: &str) -> Option<Self> {
+        let (rules, allowed_symbols) = match rules.split_once("allowed=") {
+            Some((rules, allowed)) => (rules.trim_end_matches(','), Some(allowed.to_string())),
+            None => (rules, None),
+        };
+
+        let mut policy = Self {
+            allowed_symbols,
+            ..Self::default()
+        };
+
+        for rule in rules.split(',').map(str::trim).filter(|x| !x.is_empty()) {
+            match rule.split_once('=') {
+                Some(("min", value)) => policy.min_length = value.parse().ok()?,
+                Some(("max", value)) => policy.max_length = Some(value.parse().ok()?),
+                Some(("forbid", value)) => {
+                    policy.forbidden = value
+                        .split('/')
+                        .filter(|x| !x.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                }
+                Some(_) => return None,
+                None => match rule {
+                    "upper" => policy.uppercase = true,
+                    "lower" => policy.lowercase = true,
+                    "digits" => policy.digits = true,
+                    "symbols" => policy.symbols = true,
+                    _ => return None,
+                },
+            }
+        }
+
+        Some(policy)
+    }
+
+    /// Returns a short description of the policy for showing next to an account
+    pub fn summary(&self) -> String {
+        let mut summary = vec![match self.max_length {
+            Some(max_length) => format!("{}-{} chars", self.min_length, max_length),
+            None => format!("{}+ chars", self.min_length),
+        }];
+
+        for (required, name) in [
+            (self.uppercase, "upper"),
+            (self.lowercase, "lower"),
+            (self.digits, "digits"),
+            (self.symbols, "symbols"),
+        ] {
+            if required {
+                summary.push(name.to_string());
+            }
+        }
+        if let Some(allowed) = &self.allowed_symbols {
+            summary.push(format!("only {}", allowed));
+        }
+        if !self.forbidden.is_empty() {
+            summary.push(format!("no {}", self.forbidden.join("/")));
+        }
+
+        summary.join(" · ")
+    }
+}
+
+/// Returns whether the character counts as a symbol
+fn is_symbol(c: char) -> bool {
+    !c.is_alphanumeric() && !c.is_whitespace()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Policy;
+    use crate::generator::{DerivationOptions, GeneratorOptions};
+
+    #[test]
+    /// Tests that constrained generator options always produce passwords that follow the policy
+    fn generated_passwords_follow_policy() {
+        let policy =
+            Policy::from_rules("min=10,max=12,upper,symbols,forbid=a/b,allowed=#").unwrap();
+
+        let mut options = GeneratorOptions::default();
+        options.set_length(30);
+        options.set_uppercase(false);
+        options.set_symbols(false);
+        policy.constrain(&mut options);
+
+        for _ in 0..50 {
+            let password = policy.retry(|| options.generate()).unwrap();
+            assert_eq!(policy.check(&password), Ok(()));
+            assert_eq!(password.chars().count(), 12);
+        }
+    }
+
+    #[test]
+    /// Tests that a site accepting no symbols turns them off instead of leaving an empty set
+    fn constrain_handles_no_allowed_symbols() {
+        let policy = Policy::from_rules("min=8,allowed=").unwrap();
+
+        let mut options = GeneratorOptions::default();
+        policy.constrain(&mut options);
+        assert!(!options.symbols());
+
+        let password = policy.retry(|| options.generate()).unwrap();
+        assert_eq!(policy.check(&password), Ok(()));
+    }
+
+    #[test]
+    /// Tests that constrained derivation options fit the policy's length and symbols
+    fn constrain_derivation_works() {
+        let policy = Policy::from_rules("min=40,digits,allowed=!@#").unwrap();
+
+        let mut options = DerivationOptions::new("example.org".to_string(), String::new());
+        options.set_digits(false);
+        policy.constrain_derivation(&mut options);
+
+        assert_eq!(options.length(), 35);
+        assert!(options.digits());
+        assert!(!options.symbols());
+    }
+}
@@ -0,0 +1,386 @@
+//! Scripted rotation for machine credentials like database users and API tokens. An account can
+//! have a local script that changes its password on the service it's for, so the password manager
+//! can generate a new password and only keep it once the script says the change worked
+//!
+//! Scripts are run with the shell, and are given the old password then the new password on stdin,
+//! one per line, so passwords with line breaks are refused. The account's label and username are
+//! in the `ACCOUNT_LABEL` and `ACCOUNT_USERNAME` environment variables. A script that exits with
+//! status 0 has changed the password; anything else, or taking longer than a minute, leaves the
+//! old password in place
+
+use std::io::{ErrorKind, Read, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::account::Account;
+use crate::generator::GeneratorOptions;
+use crate::time::DAY;
+
+/// How long a script can run before it's stopped and the rotation counted as failed
+const SCRIPT_TIMEOUT: Duration = Duration::from_secs(60);
+/// How often a running script is checked on
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A struct that stores the script an account's password is rotated with, and how often
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RotationScript {
+    command: String,
+    interval: u64,
+}
+
+/// A struct that stores how a run of a rotation script went
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptRun {
+    /// When the script ran, in seconds since the Unix epoch
+    pub time: u64,
+    /// Whether the password was changed, or why it wasn't
+    pub result: Result<(), String>,
+}
+
+impl RotationScript {
+    /// Creates a rotation script
+    ///
+    /// # Arguments
+    ///
+    /// * `command`  - The shell command to run
+    /// * `interval` - How many days the password is kept before it's due to be rotated
+    pub fn new<T: ToString>(command: T, interval: u64) -> Self {
+        Self {
+            command: command.to_string(),
+            interval,
+        }
+    }
+
+    /// Returns the shell command that's run
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// Returns how many days the password is kept before it's due to be rotated
+    pub fn interval(&self) -> u64 {
+        self.interval
+    }
+
+    /// Returns whether a password is due to be rotated
+    ///
+    /// # Arguments
+    ///
+    /// * `changed` - When the password last changed, if that's known
+    /// * `now`     - The current time, in seconds since the Unix epoch
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::rotation::RotationScript;
+    /// # use password_manager::time::DAY;
+    /// let script = RotationScript::new("./rotate.sh", 30);
+    ///
+    /// assert!(!script.is_due(Some(0), 29 * DAY));
+    /// assert!(script.is_due(Some(0), 30 * DAY));
+    /// assert!(script.is_due(None, 0));
+    /// ```
+    pub fn is_due(&self, changed: Option<u64>, now: u64) -> bool {
+        match changed {
+            Some(changed) => now >= changed.saturating_add(self.interval.saturating_mul(DAY)),
+            // A password of unknown age may never have been rotated
+            None => true,
+        }
+    }
+
+    /// Runs the script to change an account's password from the old one to the new one
+    ///
+    /// # Arguments
+    ///
+    /// * `account`      - The account the password is for
+    /// * `old_password` - The password the service has now
+    /// * `new_password` - The password to change it to
+    ///
+    /// # Returns
+    ///
+    /// An error with the script's last line of stderr if it didn't exit with status 0, or if
+    /// either password has a line break, which would split it across the lines of stdin
+    pub fn run(
+        &self,
+        account: &Account,
+        old_password: &str,
+        new_password: &str,
+    ) -> Result<(), String> {
+        if [old_password, new_password]
+            .iter()
+            .any(|password| password.contains(['\n', '\r']))
+        {
+            return Err(
+                "Passwords with line breaks can't be given to a rotation script".to_string(),
+            );
+        }
+
+        let mut child = shell(&self.command)
+            .env("ACCOUNT_LABEL", account.label())
+            .env("ACCOUNT_USERNAME", account.username().unwrap_or_default())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Couldn't run the rotation script: {}", e))?;
+
+        // stderr is read on another thread so a chatty script can't fill the pipe and stall
+        let mut stderr = child.stderr.take();
+        let reader = thread::spawn(move || {
+            let mut output = String::new();
+            if let Some(stderr) = stderr.as_mut() {
+                let _ = stderr.read_to_string(&mut output);
+            }
+            output
+        });
+
+        if let Some(mut stdin) = child.stdin.take() {
+            let input = format!("{}\n{}\n", old_password, new_password);
+            match stdin.write_all(input.as_bytes()) {
+                // Scripts don't have to read both passwords
+                Err(e) if e.kind() != ErrorKind::BrokenPipe => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(format!("Couldn't pass the passwords to the script: {}", e));
+                }
+                _ => (),
+            }
+        }
+
+        let started = Instant::now();
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if started.elapsed() >= SCRIPT_TIMEOUT => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(format!(
+                        "The rotation script took longer than {} seconds",
+                        SCRIPT_TIMEOUT.as_secs()
+                    ));
+                }
+                Ok(None) => thread::sleep(POLL_INTERVAL),
+                Err(e) => return Err(format!("Couldn't wait for the rotation script: {}", e)),
+            }
+        };
+        let stderr = reader.join().unwrap_or_default();
+
+        if status.success() {
+            return Ok(());
+        }
+        let exit = match status.code() {
+            Some(code) => format!("The rotation script exited with status {}", code),
+            None => "The rotation script was stopped by a signal".to_string(),
+        };
+        match stderr.lines().rev().map(str::trim).find(|x| !x.is_empty()) {
+            Some(line) => Err(format!("{}: {}", exit, line)),
+            None => Err(exit),
+        }
+    }
+}
+
+/// Returns a command that runs a line with the platform's shell
+fn shell(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    }
+}
+
+/// Rotates an account's password with its script. A new password is generated, following the
+/// account's policy, unless the account is already being rotated, in which case the script changes
+/// it to the pending password. The new password is only kept if the script works, and how it went
+/// is recorded on the account
+///
+/// # Arguments
+///
+/// * `account` - The account to rotate
+/// * `master`  - The master password derived passwords come from
+/// * `now`     - The current time, in seconds since the Unix epoch
+///
+/// # Returns
+///
+/// Why the password couldn't be rotated, if it couldn't
+pub fn rotate(account: &mut Account, master: &str, now: u64) -> Result<(), String> {
+    let Some(script) = account.rotation_script() else {
+        return Err("This account doesn't have a rotation script".to_string());
+    };
+
+    let started_here = !account.is_rotating();
+    let result = run_script(account, &script, master, started_here);
+    match (&result, started_here) {
+        (Ok(()), _) => account.confirm_rotation(master),
+        // A rotation started by hand is left for the user to finish
+        (Err(_), true) => account.cancel_rotation(),
+        (Err(_), false) => (),
+    }
+
+    account.record_script_run(&ScriptRun {
+        time: now,
+        result: result.clone(),
+    });
+    result
+}
+
+/// Works out the old and new passwords and runs the script with them
+///
+/// # Arguments
+///
+/// * `account`        - The account to rotate
+/// * `script`         - The account's rotation script
+/// * `master`         - The master password derived passwords come from
+/// * `start_rotation` - Whether to start a rotation to a new password first
+fn run_script(
+    account: &mut Account,
+    script: &RotationScript,
+    master: &str,
+    start_rotation: bool,
+) -> Result<(), String> {
+    let old_password = account.resolve_password(master)?;
+    if start_rotation {
+        let new_password = match account.derivation() {
+            // Derived passwords rotate to the next counter
+            Some(_) => String::new(),
+            None => generate_password(account)?,
+        };
+        account.start_rotation(new_password);
+    }
+    let new_password = account
+        .pending_password(master)
+        .unwrap_or_else(|| Err("There's no password to rotate to".to_string()))?;
+
+    // Derived passwords can't be regenerated until they follow the policy, so one that doesn't
+    // is refused before the site sees it
+    if let (Some(_), Some(policy)) = (account.derivation(), account.policy()) {
+        policy
+            .check(&new_password)
+            .map_err(|e| format!("The site's policy says the new password {}", e))?;
+    }
+
+    script.run(account, &old_password, &new_password)
+}
+
+/// Generates a new password for an account, following its policy if it has one
+fn generate_password(account: &Account) -> Result<String, String> {
+    let mut options = GeneratorOptions::default();
+    match account.policy() {
+        Some(policy) => {
+            policy.constrain(&mut options);
+            policy.retry(|| options.generate())
+        }
+        None => options.generate(),
+    }
+}
+
+/// Returns the positions of the accounts whose passwords are due to be rotated by their scripts
+///
+/// # Arguments
+///
+/// * `accounts` - The accounts in the vault
+/// * `now`      - The current time, in seconds since the Unix epoch
+pub fn due(accounts: &[Account], now: u64) -> Vec<usize> {
+    accounts
+        .iter()
+        .enumerate()
+        .filter_map(|(index, account)| {
+            let script = account.rotation_script()?;
+            script
+                .is_due(account.password_changed(), now)
+                .then_some(index)
+        })
+        .collect()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::{due, rotate, RotationScript};
+    use crate::account::Account;
+    use crate::generator::DerivationOptions;
+    use crate::time::DAY;
+
+    fn scripted(command: &str) -> Account {
+        let mut account = Account::builder().label("db").password("old").build();
+        account.set_rotation_script(Some(RotationScript::new(command, 30)));
+        account
+    }
+
+    #[test]
+    /// The new password is kept once the script has been given both passwords and exits with 0
+    fn keeps_password_when_script_succeeds() {
+        let mut account = scripted(
+            "read old; read new; [ \"$old\" = old ] && [ ${#new} -ge 20 ] \
+             && [ \"$ACCOUNT_LABEL\" = db ]",
+        );
+
+        assert_eq!(rotate(&mut account, "master", 5), Ok(()));
+        assert_ne!(account.password(), "old");
+        assert!(!account.is_rotating());
+
+        let run = account.last_script_run().unwrap();
+        assert_eq!((run.time, run.result), (5, Ok(())));
+    }
+
+    #[test]
+    /// A failing script leaves the old password in place and its error is recorded
+    fn keeps_old_password_when_script_fails() {
+        let mut account = scripted("echo 'access denied' >&2; exit 3");
+
+        let error = "The rotation script exited with status 3: access denied".to_string();
+        assert_eq!(rotate(&mut account, "master", 5), Err(error.clone()));
+        assert_eq!(account.password(), "old");
+        assert!(!account.is_rotating());
+        assert_eq!(account.last_script_run().unwrap().result, Err(error));
+    }
+
+    #[test]
+    /// Passwords with line breaks are refused instead of being split across lines of stdin
+    fn refuses_passwords_with_line_breaks() {
+        let mut account = scripted("exit 0");
+        account.set_password("old\nnew".to_string());
+
+        let error = "Passwords with line breaks can't be given to a rotation script".to_string();
+        assert_eq!(rotate(&mut account, "master", 5), Err(error));
+        assert_eq!(account.password(), "old\nnew");
+        assert!(!account.is_rotating());
+    }
+
+    #[test]
+    /// A rotation started by hand is finished by the script with the pending password
+    fn uses_pending_password() {
+        let mut account = scripted("read old; read new; [ \"$new\" = pending ]");
+        account.start_rotation("pending".to_string());
+
+        assert_eq!(rotate(&mut account, "master", 0), Ok(()));
+        assert_eq!(account.password(), "pending");
+    }
+
+    #[test]
+    /// Derived passwords rotate to the next counter
+    fn rotates_derived_password() {
+        let mut account = scripted("exit 0");
+        account.set_derivation(Some(DerivationOptions::new("db", "admin")));
+
+        assert_eq!(rotate(&mut account, "master", 0), Ok(()));
+        assert_eq!(account.derivation().unwrap().counter(), 2);
+    }
+
+    #[test]
+    /// Only accounts with a script are due, once their interval has passed
+    fn finds_due_accounts() {
+        let mut accounts = vec![
+            scripted("exit 0"),
+            scripted("exit 0"),
+            Account::builder().password("x").build(),
+        ];
+        accounts[1].set_password("new".to_string());
+        let changed = accounts[1].password_changed().unwrap();
+
+        assert_eq!(due(&accounts, changed), vec![0]);
+        assert_eq!(due(&accounts, changed + 30 * DAY), vec![0, 1]);
+    }
+}
@@ -4,18 +4,23 @@
 //! # File Format
 //!
 //! 1. file_password
-//! 2. account_one_label
-//! 3. account_one_username
-//! 4. account_one_email
-//! 5. account_one_password
-//! 6. account_two_label
-//! 7. account_two_username
-//! 8. account_two_email
-//! 9. account_two_password
+//! 2. #2
+//! 3. account_one_label
+//! 4. account_one_username
+//! 5. account_one_email
+//! 6. account_one_password
+//! 7. account_one_metadata
+//! 8. account_two_label
+//! 9. account_two_username
+//! 10. account_two_email
+//! 11. account_two_password
+//! 12. account_two_metadata
 //!
 //! etc...
 //!
-//! Accounts that do not have an email or a username have blank lines instead
+//! Accounts that do not have an email, a username or any metadata have blank lines instead. The
+//! `#2` line marks the format version, and files written before it existed have no version line
+//! and no metadata lines
 
 use magic_crypt::{new_magic_crypt, MagicCryptTrait};
 
@@ -27,6 +32,10 @@ use std::io::{
 
 use crate::account::Account;
 
+/// The line that marks a file as having a metadata line for every account. Encrypted lines are
+/// base64, so they never start with `#`
+const VERSION_LINE: &str = "#2";
+
 pub enum DeserialisationResult {
     WrongPassword,
     Ok(Vec<Account>),
@@ -74,7 +83,16 @@ pub fn deserialise(password_file_contents: String, password: &str) -> Deserialis
         return DeserialisationResult::WrongPassword;
     }
 
-    for _ in 0..(lines.clone().count() / 4) {
+    let has_metadata = lines.clone().next() == Some(VERSION_LINE);
+    if has_metadata {
+        lines.next();
+    }
+    let lines_per_account = match has_metadata {
+        true => 5,
+        false => 4,
+    };
+
+    for _ in 0..(lines.clone().count() / lines_per_account) {
         let mut account_builder = Account::builder();
 
         account_builder.label(lines.next().expect("Should be safe to unwrap"));
@@ -91,6 +109,10 @@ pub fn deserialise(password_file_contents: String, password: &str) -> Deserialis
 
         account_builder.password(lines.next().expect("Should be safe to unwrap"));
 
+        if has_metadata {
+            account_builder.metadata(lines.next().expect("Should be safe to unwrap"));
+        }
+
         accounts.push(account_builder.decrypt(decrypter.clone()).build());
     }
     DeserialisationResult::Ok(accounts)
@@ -111,6 +133,7 @@ pub fn serialise(accounts: Vec<Account>, password_file: &str, password: &str) ->
 
     writeln!(file, "{}", encrypter.encrypt_str_to_base64(password))
         .expect("Failed to write passkey");
+    writeln!(file, "{}", VERSION_LINE)?;
 
     for account in accounts {
         writeln!(file, "{}", account.encrypt(&encrypter))?;
@@ -120,3 +143,51 @@ pub fn serialise(accounts: Vec<Account>, password_file: &str, password: &str) ->
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{deserialise, read_password_file, serialise, DeserialisationResult};
+    use crate::account::Account;
+    use magic_crypt::{new_magic_crypt, MagicCryptTrait};
+
+    #[test]
+    /// Tests that accounts keep their metadata when saved, and that files written before metadata
+    /// existed can still be read
+    fn serialisation_round_trips() {
+        let account = Account::builder()
+            .label("Label")
+            .username("user")
+            .password("hunter2")
+            .metadata("derive.counter=3")
+            .build();
+
+        let file = std::env::temp_dir().join("password_manager_serialisation_test.txt");
+        let file = file.to_str().unwrap();
+        serialise(vec![account], file, "master").unwrap();
+        let contents = read_password_file(file).unwrap();
+        std::fs::remove_file(file).unwrap();
+
+        let DeserialisationResult::Ok(accounts) = deserialise(contents, "master") else {
+            panic!("The password should be right");
+        };
+        assert_eq!(accounts[0].password(), "hunter2");
+        assert_eq!(accounts[0].metadata().get("derive.counter"), Some("3"));
+
+        let encrypter = new_magic_crypt!("master", 256);
+        let legacy = [
+            encrypter.encrypt_str_to_base64("master"),
+            encrypter.encrypt_str_to_base64("Label"),
+            String::new(),
+            encrypter.encrypt_str_to_base64("me@example.com"),
+            encrypter.encrypt_str_to_base64("hunter2"),
+        ]
+        .join("\n");
+
+        let DeserialisationResult::Ok(accounts) = deserialise(legacy, "master") else {
+            panic!("The password should be right");
+        };
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].email(), Some("me@example.com".to_string()));
+        assert!(accounts[0].metadata().is_empty());
+    }
+}
@@ -0,0 +1,317 @@
+//! A module that estimates how strong a password is, by how many guesses an attacker would need
+//! to find it
+//!
+//! Estimation follows zxcvbn: the password is split into the guessable patterns it contains, such
+//! as dictionary words, rows of keys, dates, sequences and repeats, and the split needing the
+//! fewest guesses is taken as the password's strength. Anything not covered by a pattern is
+//! assumed to be brute forced
+
+mod matching;
+
+use colored::Colorize;
+use matching::{find_matches, Match, Pattern};
+
+/// Guesses per second an offline attacker makes against a leaked database of fast, unsalted hashes
+const GUESSES_PER_SECOND: f64 = 1e10;
+/// Characters past this length aren't checked for patterns, to keep estimating quick while typing
+const MAX_LENGTH: usize = 100;
+/// The base used to brute force characters that aren't part of a pattern
+const BRUTEFORCE_CARDINALITY: f64 = 10.0;
+/// The fewest guesses a pattern can take when it doesn't cover the whole password, so short
+/// patterns aren't favoured over brute force
+const MIN_SINGLE_GUESSES: f64 = 10.0;
+const MIN_GUESSES: f64 = 50.0;
+/// The powers of ten of guesses each score starts at
+const SCORE_THRESHOLDS: [f64; 4] = [3.0, 6.0, 8.0, 10.0];
+const LABELS: [&str; 5] = ["very weak", "weak", "fair", "strong", "very strong"];
+const TIME_UNITS: [(f64, &str); 6] = [
+    (60.0, "second"),
+    (60.0, "minute"),
+    (24.0, "hour"),
+    (30.0, "day"),
+    (12.0, "month"),
+    (100.0, "year"),
+];
+
+/// The guesses needed for part of a password, as a power of ten, along with the index of the
+/// pattern that ends it, or `None` for a brute forced run, and where that part starts
+type Split = (f64, Option<usize>, usize);
+
+#[derive(Debug, Clone, PartialEq)]
+/// A struct that stores how strong a password is
+pub struct Estimate {
+    guesses_log10: f64,
+    score: u8,
+    warning: Option<&'static str>,
+}
+
+impl Estimate {
+    /// Returns the power of ten of guesses an attacker needs to find the password
+    pub fn guesses_log10(&self) -> f64 {
+        self.guesses_log10
+    }
+
+    /// Returns the strength of the password, from 0 for very weak to 4 for very strong
+    pub fn score(&self) -> u8 {
+        self.score
+    }
+
+    /// Returns the name of the score
+    pub fn label(&self) -> &'static str {
+        LABELS[self.score as usize]
+    }
+
+    /// Returns roughly how long an offline attacker with the site's leaked hashes would take to
+    /// find the password
+    pub fn crack_time(&self) -> String {
+        let mut time = 10f64.powf(self.guesses_log10) / GUESSES_PER_SECOND;
+        if time < 1.0 {
+            return "less than a second".to_string();
+        }
+
+        for (size, unit) in TIME_UNITS {
+            if time < size {
+                let time = time.round() as u64;
+                return match time {
+                    1 => format!("1 {}", unit),
+                    time => format!("{} {}s", time, unit),
+                };
+            }
+            time /= size;
+        }
+        "centuries".to_string()
+    }
+
+    /// Returns what makes the password weak, if it is weak
+    pub fn warning(&self) -> Option<&'static str> {
+        self.warning
+    }
+
+    /// Returns a coloured meter of the password's strength, for drawing next to it
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use password_manager::strength::estimate;
+    /// colored::control::set_override(false);
+    /// assert_eq!(
+    ///     estimate("password").meter(),
+    ///     "▯▯▯▯ very weak · less than a second to crack"
+    /// );
+    /// ```
+    pub fn meter(&self) -> String {
+        let bars = format!(
+            "{}{}",
+            "▮".repeat(self.score as usize),
+            "▯".repeat(4 - self.score as usize)
+        );
+        let meter = format!("{} {} · {} to crack", bars, self.label(), self.crack_time());
+
+        match self.score {
+            0 | 1 => meter.red().to_string(),
+            2 => meter.yellow().to_string(),
+            _ => meter.green().to_string(),
+        }
+    }
+}
+
+/// Estimates how strong a password is
+///
+/// # Arguments
+///
+/// * `password` - The password to estimate
+///
+/// # Example
+///
+/// ```
+/// # use password_manager::strength::estimate;
+/// assert_eq!(estimate("qwerty123").score(), 0);
+/// assert!(estimate("correct horse battery staple").score() >= 3);
+/// ```
+pub fn estimate(password: &str) -> Estimate {
+    let chars: Vec<char> = password.chars().take(MAX_LENGTH).collect();
+    let (guesses_log10, matches) = most_guessable(&chars);
+    // Characters past the limit are counted as brute forced
+    let guesses_log10 = guesses_log10 + password.chars().skip(MAX_LENGTH).count() as f64;
+
+    let score = SCORE_THRESHOLDS
+        .iter()
+        .filter(|threshold| guesses_log10 >= **threshold)
+        .count() as u8;
+
+    let warning = match score {
+        0..=2 => warning(&matches, chars.len()),
+        _ => None,
+    };
+
+    Estimate {
+        guesses_log10,
+        score,
+        warning,
+    }
+}
+
+/// Returns the power of ten of guesses needed to find the characters, for estimating the parts
+/// of a repeated pattern
+fn guesses_log10(chars: &[char]) -> f64 {
+    most_guessable(chars).0
+}
+
+/// Finds the split of the characters into patterns and brute forced runs that needs the fewest
+/// guesses
+///
+/// # Returns
+///
+/// The power of ten of guesses needed, and the patterns the split uses
+fn most_guessable(chars: &[char]) -> (f64, Vec<Match>) {
+    let length = chars.len();
+    if length == 0 {
+        return (0.0, vec![]);
+    }
+
+    let mut matches = find_matches(chars);
+    for m in matches.iter_mut() {
+        let min = match m.end - m.start {
+            x if x == length => 1.0,
+            1 => MIN_SINGLE_GUESSES,
+            _ => MIN_GUESSES,
+        };
+        m.guesses = m.guesses.max(min);
+    }
+
+    // best[end][count] is the fewest guesses, as a power of ten, for the first `end` characters
+    // split into `count` parts, along with the part that ends the split
+    let mut best: Vec<Vec<Option<Split>>> = vec![vec![None; length + 1]; length + 1];
+    best[0][0] = Some((0.0, None, 0));
+
+    for end in 1..=length {
+        for count in 1..=end {
+            let mut candidates = vec![];
+
+            // Either a brute forced run, which is never split into smaller runs, or a pattern
+            for (start, splits) in best.iter().enumerate().take(end) {
+                if let Some((guesses, part, _)) = splits[count - 1] {
+                    let follows_run = part.is_none() && start != 0;
+                    if !follows_run {
+                        let run = (end - start) as f64 * BRUTEFORCE_CARDINALITY.log10();
+                        candidates.push((guesses + run, None, start));
+                    }
+                }
+            }
+            for (i, m) in matches.iter().enumerate().filter(|(_, m)| m.end == end) {
+                if let Some((guesses, _, _)) = best[m.start][count - 1] {
+                    candidates.push((guesses + m.guesses.log10(), Some(i), m.start));
+                }
+            }
+
+            best[end][count] = candidates.into_iter().min_by(|x, y| x.0.total_cmp(&y.0));
+        }
+    }
+
+    // The order of the parts also has to be guessed
+    let (count, guesses) = (1..=length)
+        .filter_map(|count| {
+            let (guesses, _, _) = best[length][count]?;
+            let order: f64 = (1..=count).map(|x| (x as f64).log10()).sum();
+            Some((count, guesses + order))
+        })
+        .min_by(|x, y| x.1.total_cmp(&y.1))
+        .unwrap_or((1, length as f64));
+
+    let mut parts = vec![];
+    let (mut end, mut count) = (length, count);
+    while count > 0 {
+        let Some((_, part, start)) = best[end][count] else {
+            break;
+        };
+        if let Some(i) = part {
+            parts.push(matches[i].clone());
+        }
+        end = start;
+        count -= 1;
+    }
+    parts.reverse();
+
+    (guesses, parts)
+}
+
+/// Returns what makes a password weak, from the longest pattern it's made of
+///
+/// # Arguments
+///
+/// * `matches` - The patterns the password is made of
+/// * `length`  - The length of the password
+fn warning(matches: &[Match], length: usize) -> Option<&'static str> {
+    if length == 0 {
+        return Some("There's no password");
+    }
+
+    let longest = match matches.iter().max_by_key(|m| m.end - m.start) {
+        Some(longest) => longest,
+        None if length < 8 => return Some("Short passwords are easy to guess"),
+        None => return None,
+    };
+    let whole = longest.end - longest.start == length;
+
+    Some(match longest.pattern {
+        Pattern::CommonPassword if whole => "This is a very common password",
+        Pattern::CommonPassword => "This contains a very common password",
+        Pattern::Word if longest.l33t => "Swapping letters for symbols doesn't hide a word",
+        Pattern::Word if whole => "A word by itself is easy to guess",
+        Pattern::Word => "Dictionary words are easy to guess",
+        Pattern::Keyboard => "Rows of keys are easy to guess",
+        Pattern::Sequence => "Sequences like abc or 6543 are easy to guess",
+        Pattern::Repeat => "Repeated characters are easy to guess",
+        Pattern::Date => "Dates and years are easy to guess",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::estimate;
+
+    #[test]
+    /// Tests that passwords made of guessable patterns score low and random ones score high
+    fn estimate_scores_patterns_low() {
+        for weak in [
+            "",
+            "password1",
+            "P@ssw0rd",
+            "qwerty",
+            "abcdef",
+            "19901231",
+            "aaaaaaaaaa",
+            "dragon2024",
+        ] {
+            let estimate = estimate(weak);
+            assert!(
+                estimate.score() <= 1,
+                "{} scored {}",
+                weak,
+                estimate.score()
+            );
+            assert!(estimate.warning().is_some(), "{} has no warning", weak);
+        }
+
+        for strong in ["k#9Vq2!xTz@4Lm8w", "Tr0ub4dor&3-horse-Zebra!"] {
+            let estimate = estimate(strong);
+            assert!(
+                estimate.score() >= 3,
+                "{} scored {}",
+                strong,
+                estimate.score()
+            );
+            assert_eq!(estimate.warning(), None);
+        }
+    }
+
+    #[test]
+    /// Tests that crack times are written in the largest unit that fits
+    fn crack_time_uses_largest_unit() {
+        assert_eq!(estimate("a").crack_time(), "less than a second");
+        assert_eq!(
+            estimate("k#9Vq2!xTz@4Lm8w&P3r%Yb7Nj").crack_time(),
+            "centuries"
+        );
+    }
+}
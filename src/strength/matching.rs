@@ -0,0 +1,565 @@
+//! Finds the guessable patterns in a password, and how many guesses each would take an attacker
+//! who knows to look for it
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::generator::wordlist;
+
+const COMMON_PASSWORDS: &str = include_str!("passwords.txt");
+
+/// The rank given to dictionary words, which aren't ordered by how common they are
+const WORD_RANK: usize = 1000;
+/// Letters that are commonly swapped for look-alike characters
+const L33T: [(char, char); 20] = [
+    ('4', 'a'),
+    ('@', 'a'),
+    ('8', 'b'),
+    ('(', 'c'),
+    ('{', 'c'),
+    ('<', 'c'),
+    ('3', 'e'),
+    ('6', 'g'),
+    ('9', 'g'),
+    ('1', 'i'),
+    ('!', 'i'),
+    ('|', 'i'),
+    ('1', 'l'),
+    ('7', 'l'),
+    ('0', 'o'),
+    ('$', 's'),
+    ('5', 's'),
+    ('+', 't'),
+    ('7', 't'),
+    ('2', 'z'),
+];
+/// The rows of a qwerty keyboard, unshifted and shifted
+const KEYBOARD: [(&str, &str); 4] = [
+    ("`1234567890-=", "~!@#$%^&*()_+"),
+    ("qwertyuiop[]\\", "QWERTYUIOP{}|"),
+    ("asdfghjkl;'", "ASDFGHJKL:\""),
+    ("zxcvbnm,./", "ZXCVBNM<>?"),
+];
+/// How far each keyboard row is shifted to the right, in keys
+const ROW_OFFSETS: [f64; 4] = [0.0, 1.5, 1.75, 2.25];
+/// The number of keys a keyboard pattern can start on, and the average number of neighbours
+const KEYBOARD_KEYS: f64 = 94.0;
+const KEYBOARD_DEGREE: f64 = 4.6;
+/// Dates this close to now are assumed to be guessed first
+const MIN_YEAR_SPACE: i64 = 20;
+const DATE_SEPARATORS: &str = "/\\_.- ";
+
+/// Enum of the kinds of pattern that can be found in a password
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Pattern {
+    /// One of the most common passwords
+    CommonPassword,
+    /// A dictionary word
+    Word,
+    /// Neighbouring keys on a keyboard, like `qwerty`
+    Keyboard,
+    /// Characters in order, like `abc` or `9876`
+    Sequence,
+    /// The same characters repeated, like `aaa` or `abcabc`
+    Repeat,
+    /// A year or date
+    Date,
+}
+
+/// A guessable pattern found in a password
+#[derive(Debug, Clone)]
+pub(super) struct Match {
+    /// The index of the first character of the pattern
+    pub start: usize,
+    /// The index after the last character of the pattern
+    pub end: usize,
+    /// How many guesses it takes to find the pattern
+    pub guesses: f64,
+    /// The kind of pattern
+    pub pattern: Pattern,
+    /// Whether letters were swapped for look-alike characters
+    pub l33t: bool,
+}
+
+/// Finds every guessable pattern in the password
+///
+/// # Arguments
+///
+/// * `password` - The characters of the password
+pub(super) fn find_matches(password: &[char]) -> Vec<Match> {
+    let mut matches = dictionary_matches(password);
+    matches.extend(keyboard_matches(password));
+    matches.extend(sequence_matches(password));
+    matches.extend(repeat_matches(password));
+    matches.extend(date_matches(password));
+    matches
+}
+
+/// Returns every dictionary entry with its rank, common passwords first
+fn dictionary() -> &'static HashMap<String, (usize, Pattern)> {
+    static DICTIONARY: OnceLock<HashMap<String, (usize, Pattern)>> = OnceLock::new();
+    DICTIONARY.get_or_init(|| {
+        let mut dictionary = HashMap::new();
+        for word in wordlist() {
+            dictionary.insert(word.to_string(), (WORD_RANK, Pattern::Word));
+        }
+        for (rank, password) in COMMON_PASSWORDS.lines().enumerate() {
+            dictionary.insert(password.to_string(), (rank + 1, Pattern::CommonPassword));
+        }
+        dictionary
+    })
+}
+
+/// Finds dictionary words and common passwords, including reversed ones and ones with letters
+/// swapped for look-alike characters
+fn dictionary_matches(password: &[char]) -> Vec<Match> {
+    let dictionary = dictionary();
+    let mut matches = vec![];
+
+    for start in 0..password.len() {
+        for end in (start + 3)..=password.len() {
+            let token = &password[start..end];
+            let lowercase: String = token.iter().flat_map(|c| c.to_lowercase()).collect();
+            let reversed: String = lowercase.chars().rev().collect();
+
+            let mut candidates = vec![(lowercase.clone(), 1.0, false)];
+            candidates.push((reversed, 2.0, false));
+            for (unl33ted, variations) in unl33t(token) {
+                candidates.push((unl33ted, variations, true));
+            }
+
+            let best = candidates
+                .into_iter()
+                .filter_map(|(word, variations, l33t)| {
+                    let (rank, pattern) = dictionary.get(&word)?;
+                    Some((*rank as f64 * variations, *pattern, l33t))
+                })
+                .min_by(|x, y| x.0.total_cmp(&y.0));
+
+            if let Some((guesses, pattern, l33t)) = best {
+                matches.push(Match {
+                    start,
+                    end,
+                    guesses: guesses * uppercase_variations(token),
+                    pattern,
+                    l33t,
+                });
+            }
+        }
+    }
+
+    matches
+}
+
+/// Returns the ways the token could read with its look-alike characters swapped back for letters,
+/// with how many variations an attacker would try to find each
+fn unl33t(token: &[char]) -> Vec<(String, f64)> {
+    let lowercase: Vec<char> = token.iter().flat_map(|c| c.to_lowercase()).collect();
+    let mut translations: Vec<Vec<(char, char)>> = vec![vec![]];
+
+    let mut subbed: Vec<char> = lowercase
+        .iter()
+        .copied()
+        .filter(|c| L33T.iter().any(|(x, _)| x == c))
+        .collect();
+    subbed.sort_unstable();
+    subbed.dedup();
+    if subbed.is_empty() {
+        return vec![];
+    }
+
+    // Each substituted character can stand for more than one letter, so every combination is
+    // tried
+    for c in subbed {
+        let letters: Vec<char> = L33T
+            .iter()
+            .filter(|(x, _)| *x == c)
+            .map(|(_, letter)| *letter)
+            .collect();
+        translations = translations
+            .into_iter()
+            .flat_map(|translation| {
+                letters.iter().map(move |letter| {
+                    let mut translation = translation.clone();
+                    translation.push((c, *letter));
+                    translation
+                })
+            })
+            .collect();
+    }
+
+    translations
+        .into_iter()
+        .map(|translation| {
+            let word = lowercase
+                .iter()
+                .map(|c| match translation.iter().find(|(x, _)| x == c) {
+                    Some((_, letter)) => *letter,
+                    None => *c,
+                })
+                .collect();
+
+            let variations = translation
+                .iter()
+                .map(|(subbed, letter)| {
+                    let s = lowercase.iter().filter(|c| *c == subbed).count();
+                    let u = lowercase.iter().filter(|c| *c == letter).count();
+                    match u {
+                        0 => 2.0,
+                        u => (1..=s.min(u)).map(|i| choose(s + u, i)).sum(),
+                    }
+                })
+                .product();
+
+            (word, variations)
+        })
+        .collect()
+}
+
+/// Returns how many ways an attacker would try capitalising the token before finding it
+fn uppercase_variations(token: &[char]) -> f64 {
+    let upper = token.iter().filter(|c| c.is_uppercase()).count();
+    let lower = token.iter().filter(|c| c.is_lowercase()).count();
+
+    let only_first = token.first().is_some_and(|c| c.is_uppercase()) && upper == 1;
+    let only_last = token.last().is_some_and(|c| c.is_uppercase()) && upper == 1;
+
+    if upper == 0 {
+        1.0
+    } else if lower == 0 || only_first || only_last {
+        2.0
+    } else {
+        (1..=upper.min(lower))
+            .map(|i| choose(upper + lower, i))
+            .sum()
+    }
+}
+
+/// Returns where the key is on a qwerty keyboard, and whether it needs shift
+fn key_position(c: char) -> Option<(f64, f64, bool)> {
+    KEYBOARD
+        .iter()
+        .enumerate()
+        .find_map(|(row, (unshifted, shifted))| {
+            let (column, shift) = match unshifted.chars().position(|x| x == c) {
+                Some(column) => (column, false),
+                None => (shifted.chars().position(|x| x == c)?, true),
+            };
+            Some((row as f64, ROW_OFFSETS[row] + column as f64, shift))
+        })
+}
+
+/// Returns the direction from one key to a neighbouring key, or `None` if they aren't neighbours
+fn key_direction(from: char, to: char) -> Option<(i64, i64)> {
+    let (from_row, from_x, _) = key_position(from)?;
+    let (to_row, to_x, _) = key_position(to)?;
+    let (dy, dx) = (to_row - from_row, to_x - from_x);
+
+    let neighbours = (dy == 0.0 && dx.abs() == 1.0) || (dy.abs() == 1.0 && dx.abs() <= 0.75);
+    match neighbours {
+        true => Some((dy as i64, (dx * 4.0) as i64)),
+        false => None,
+    }
+}
+
+/// Finds runs of three or more neighbouring keys on a qwerty keyboard
+fn keyboard_matches(password: &[char]) -> Vec<Match> {
+    let mut matches = vec![];
+    let mut start = 0;
+
+    while start < password.len() {
+        let mut end = start + 1;
+        let mut turns = 0;
+        let mut direction = None;
+
+        while end < password.len() {
+            let Some(next) = key_direction(password[end - 1], password[end]) else {
+                break;
+            };
+            if direction != Some(next) {
+                turns += 1;
+                direction = Some(next);
+            }
+            end += 1;
+        }
+
+        if end - start >= 3 {
+            let length = end - start;
+            let mut guesses = 0.0;
+            for i in 2..=length {
+                for j in 1..=turns.min(i - 1) {
+                    guesses +=
+                        choose(i - 1, j - 1) * KEYBOARD_KEYS * KEYBOARD_DEGREE.powi(j as i32);
+                }
+            }
+
+            let token = &password[start..end];
+            let shifted = token
+                .iter()
+                .filter(|c| key_position(**c).is_some_and(|(_, _, shift)| shift))
+                .count();
+            guesses *= match (shifted, length - shifted) {
+                (0, _) => 1.0,
+                (_, 0) => 2.0,
+                (s, u) => (1..=s.min(u)).map(|i| choose(s + u, i)).sum(),
+            };
+
+            matches.push(Match {
+                start,
+                end,
+                guesses,
+                pattern: Pattern::Keyboard,
+                l33t: false,
+            });
+            start = end - 1;
+        } else {
+            start += 1;
+        }
+    }
+
+    matches
+}
+
+/// Finds runs of three or more characters in order, like `abc`, `XYZ` or `4321`
+fn sequence_matches(password: &[char]) -> Vec<Match> {
+    let class = |c: char| match c {
+        'a'..='z' => Some(0),
+        'A'..='Z' => Some(1),
+        '0'..='9' => Some(2),
+        _ => None,
+    };
+
+    let mut matches = vec![];
+    let mut start = 0;
+
+    while start + 1 < password.len() {
+        let delta = password[start + 1] as i64 - password[start] as i64;
+        let same_class = class(password[start]).is_some()
+            && class(password[start]) == class(password[start + 1]);
+        if delta.abs() != 1 || !same_class {
+            start += 1;
+            continue;
+        }
+
+        let mut end = start + 2;
+        while end < password.len()
+            && password[end] as i64 - password[end - 1] as i64 == delta
+            && class(password[end]) == class(password[start])
+        {
+            end += 1;
+        }
+
+        if end - start >= 3 {
+            let first = password[start];
+            let base = match first {
+                'a' | 'A' | 'z' | 'Z' | '0' | '1' | '9' => 4.0,
+                '0'..='9' => 10.0,
+                _ => 26.0,
+            };
+            let descending = if delta < 0 { 2.0 } else { 1.0 };
+
+            matches.push(Match {
+                start,
+                end,
+                guesses: base * (end - start) as f64 * descending,
+                pattern: Pattern::Sequence,
+                l33t: false,
+            });
+        }
+        start = end - 1;
+    }
+
+    matches
+}
+
+/// Finds characters or groups of characters repeated back to back, like `aaa` or `abcabc`
+fn repeat_matches(password: &[char]) -> Vec<Match> {
+    let mut matches = vec![];
+
+    for start in 0..password.len() {
+        let mut best: Option<(usize, usize)> = None;
+
+        for base_length in 1..=(password.len() - start) / 2 {
+            let base = &password[start..start + base_length];
+            let mut repeats = 1;
+            while password[start + repeats * base_length..]
+                .get(..base_length)
+                .is_some_and(|x| x == base)
+            {
+                repeats += 1;
+            }
+
+            let long_enough = repeats >= 3 || (repeats >= 2 && base_length > 1);
+            if long_enough && best.is_none_or(|(l, r)| base_length * repeats > l * r) {
+                best = Some((base_length, repeats));
+            }
+        }
+
+        if let Some((base_length, repeats)) = best {
+            let base = &password[start..start + base_length];
+            let base_guesses = match base_length {
+                1 => cardinality(base[0]),
+                _ => 10f64.powf(super::guesses_log10(base)),
+            };
+
+            matches.push(Match {
+                start,
+                end: start + base_length * repeats,
+                guesses: base_guesses * repeats as f64,
+                pattern: Pattern::Repeat,
+                l33t: false,
+            });
+        }
+    }
+
+    matches
+}
+
+/// Finds years and dates, with or without separators
+fn date_matches(password: &[char]) -> Vec<Match> {
+    let this_year = current_year();
+    let year_space = |year: i64| ((year - this_year).abs()).max(MIN_YEAR_SPACE) as f64;
+    let mut matches = vec![];
+
+    for start in 0..password.len() {
+        for end in (start + 4)..=(start + 10).min(password.len()) {
+            let token: String = password[start..end].iter().collect();
+
+            if token.len() == 4 {
+                if let Ok(year @ 1900..=2099) = token.parse::<i64>() {
+                    matches.push(Match {
+                        start,
+                        end,
+                        guesses: year_space(year),
+                        pattern: Pattern::Date,
+                        l33t: false,
+                    });
+                    continue;
+                }
+            }
+
+            if let Some((year, separated)) = parse_date(&token) {
+                let separator = if separated { 4.0 } else { 1.0 };
+                matches.push(Match {
+                    start,
+                    end,
+                    guesses: year_space(year) * 365.0 * separator,
+                    pattern: Pattern::Date,
+                    l33t: false,
+                });
+            }
+        }
+    }
+
+    matches
+}
+
+/// Reads a date written as day, month and year in any common order
+///
+/// # Returns
+///
+/// `None` if the token isn't a date, otherwise the year and whether the parts were separated
+fn parse_date(token: &str) -> Option<(i64, bool)> {
+    let separator = token.chars().find(|c| DATE_SEPARATORS.contains(*c));
+
+    let parts: Vec<&str> = match separator {
+        Some(separator) => token.split(separator).collect(),
+        None if token.len() == 6 => vec![&token[..2], &token[2..4], &token[4..]],
+        None if token.len() == 8 => {
+            return [
+                [&token[..2], &token[2..4], &token[4..]],
+                [&token[..4], &token[4..6], &token[6..]],
+            ]
+            .iter()
+            .find_map(|parts| valid_date(parts))
+            .map(|year| (year, false));
+        }
+        None => return None,
+    };
+
+    if parts.len() != 3 || parts.iter().any(|x| x.is_empty() || x.len() > 4) {
+        return None;
+    }
+    valid_date(&parts).map(|year| (year, separator.is_some()))
+}
+
+/// Returns the year of the date if the three parts make a valid one, trying day-month-year,
+/// month-day-year and year-month-day orders
+fn valid_date(parts: &[&str]) -> Option<i64> {
+    let numbers: Vec<i64> = parts
+        .iter()
+        .map(|x| match x.chars().all(|c| c.is_ascii_digit()) {
+            true => x.parse().ok(),
+            false => None,
+        })
+        .collect::<Option<Vec<i64>>>()?;
+
+    let year = |part: &str, value: i64| match part.len() {
+        2 if value > 50 => Some(1900 + value),
+        2 => Some(2000 + value),
+        4 if (1000..=2050).contains(&value) => Some(value),
+        _ => None,
+    };
+    let day_month = |day: i64, month: i64| (1..=31).contains(&day) && (1..=12).contains(&month);
+
+    let (a, b, c) = (numbers[0], numbers[1], numbers[2]);
+    if parts[0].len() <= 2 && parts[1].len() <= 2 && (day_month(a, b) || day_month(b, a)) {
+        if let Some(year) = year(parts[2], c) {
+            return Some(year);
+        }
+    }
+    if parts[1].len() <= 2 && parts[2].len() <= 2 && day_month(c, b) {
+        return year(parts[0], a);
+    }
+    None
+}
+
+/// Returns roughly what year it is
+fn current_year() -> i64 {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_secs())
+        .unwrap_or(0);
+    1970 + (seconds / 31_556_952) as i64
+}
+
+/// Returns how many characters are like the given one, for guessing it by brute force
+fn cardinality(c: char) -> f64 {
+    match c {
+        'a'..='z' | 'A'..='Z' => 26.0,
+        '0'..='9' => 10.0,
+        c if c.is_ascii() => 33.0,
+        _ => 100.0,
+    }
+}
+
+/// Returns the number of ways to choose k items from n
+fn choose(n: usize, k: usize) -> f64 {
+    (0..k).fold(1.0, |x, i| x * (n - i) as f64 / (i + 1) as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_matches, Pattern};
+
+    #[test]
+    /// Tests that each kind of pattern is found where it is in the password
+    fn find_matches_finds_patterns() {
+        let find = |password: &str, pattern: Pattern| {
+            let chars: Vec<char> = password.chars().collect();
+            find_matches(&chars)
+                .into_iter()
+                .filter(|x| x.pattern == pattern)
+                .map(|x| (x.start, x.end))
+                .max_by_key(|(start, end)| end - start)
+        };
+
+        assert_eq!(find("xxP@ssw0rdxx", Pattern::CommonPassword), Some((2, 10)));
+        assert_eq!(find("zebra", Pattern::Word), Some((0, 5)));
+        assert_eq!(find("..qwerty..", Pattern::Keyboard), Some((2, 8)));
+        assert_eq!(find("x6789x", Pattern::Sequence), Some((1, 5)));
+        assert_eq!(find("abcabcabc", Pattern::Repeat), Some((0, 9)));
+        assert_eq!(find("born 25/12/1990", Pattern::Date), Some((5, 15)));
+    }
+}
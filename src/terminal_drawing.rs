@@ -79,6 +79,31 @@ pub fn textfield<T: ToString>(
     prompt_len: u16,
     content: T,
     hide_input: bool,
+) -> Result<Option<String>> {
+    textfield_with_hint(prompt, prompt_len, content, hide_input, |_| String::new())
+}
+
+/// Clears the current line and provides a textbox for the user to type input into, with a hint
+/// about the input drawn after it that updates as the user types
+///
+/// # Arguments
+///
+/// * `prompt` - What the textbox prompt should be
+/// * `prompt_len` - The length of the prompt
+/// * `content` - The initial content of the textfield
+/// * `hide_input` - Whether the content of the textfield should be hidden with '*' chars
+/// * `hint` - Returns the hint to draw for the current input
+///
+/// # Returns
+///
+/// If no errors occured, an Option containing None if the user canceled the operation, or Some
+/// containing what the user inputted
+pub fn textfield_with_hint<T: ToString, F: Fn(&str) -> String>(
+    prompt: T,
+    prompt_len: u16,
+    content: T,
+    hide_input: bool,
+    hint: F,
 ) -> Result<Option<String>> {
     execute!(stdout(), cursor::Show, cursor::SetCursorStyle::SteadyBlock)?;
 
@@ -99,6 +124,10 @@ pub fn textfield<T: ToString>(
                     false => output.to_string(),
                 }
             )),
+            Print(match hint(&output) {
+                hint if hint.is_empty() => hint,
+                hint => format!("  {}", hint),
+            }),
             cursor::MoveToColumn(0),
             cursor::MoveRight(prompt_len + (cursor as u16))
         )?;
@@ -108,20 +137,14 @@ pub fn textfield<T: ToString>(
                     output.insert(cursor, char);
                     cursor += 1;
                 }
-                KeyCode::Backspace => {
-                    if !output.is_empty() {
-                        output.remove(cursor - 1);
-                        cursor = cursor.saturating_sub(1);
-                    }
+                KeyCode::Backspace if cursor > 0 => {
+                    output.remove(cursor - 1);
+                    cursor -= 1;
                 }
                 KeyCode::Esc => return Ok(None),
                 KeyCode::Enter => break,
                 KeyCode::Left => cursor = cursor.saturating_sub(1),
-                KeyCode::Right => {
-                    if cursor != output.len() {
-                        cursor += 1;
-                    }
-                }
+                KeyCode::Right if cursor != output.len() => cursor += 1,
                 _ => (),
             }
         }
@@ -0,0 +1,141 @@
+//! A module with the little time keeping the password manager needs. Times are stored as whole
+//! seconds since the Unix epoch, so they fit in account metadata
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The number of seconds in a day
+pub const DAY: u64 = 60 * 60 * 24;
+
+/// Returns the current time, in seconds since the Unix epoch
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_secs())
+        .unwrap_or(0)
+}
+
+/// Returns how many whole days have passed between two times
+///
+/// # Arguments
+///
+/// * `from` - The earlier time
+/// * `to`   - The later time
+///
+/// # Example
+///
+/// ```
+/// # use password_manager::time::{days_between, DAY};
+/// assert_eq!(days_between(0, 3 * DAY + 5), 3);
+/// assert_eq!(days_between(DAY, 0), 0);
+/// ```
+pub fn days_between(from: u64, to: u64) -> u64 {
+    to.saturating_sub(from) / DAY
+}
+
+/// Formats a time as a UTC date and time, like `2024-02-29 13:05:09 UTC`
+///
+/// # Arguments
+///
+/// * `seconds` - The time, in seconds since the Unix epoch
+///
+/// # Example
+///
+/// ```
+/// # use password_manager::time::format_utc;
+/// assert_eq!(format_utc(0), "1970-01-01 00:00:00 UTC");
+/// assert_eq!(format_utc(1_709_211_909), "2024-02-29 13:05:09 UTC");
+/// ```
+pub fn format_utc(seconds: u64) -> String {
+    let (year, month, day) = civil_date(seconds / DAY);
+    let time = seconds % DAY;
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+/// Formats a time as a UTC date, like `2024-02-29`
+///
+/// # Arguments
+///
+/// * `seconds` - The time, in seconds since the Unix epoch
+pub fn format_date(seconds: u64) -> String {
+    let (year, month, day) = civil_date(seconds / DAY);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Parses a UTC date like `2024-02-29` into the time it starts
+///
+/// # Arguments
+///
+/// * `date` - The date, as year, month and day separated by `-`
+///
+/// # Returns
+///
+/// `None` if the date isn't valid or is before the Unix epoch, otherwise the time in seconds since
+/// the Unix epoch
+///
+/// # Example
+///
+/// ```
+/// # use password_manager::time::{format_date, parse_date};
+/// assert_eq!(parse_date("1970-01-02"), Some(86_400));
+/// assert_eq!(parse_date("2024-02-29").map(format_date), Some("2024-02-29".to_string()));
+/// assert_eq!(parse_date("2023-02-29"), None);
+/// assert_eq!(parse_date("29/02/2024"), None);
+/// assert_eq!(parse_date("9999999999999-01-01"), None);
+/// ```
+pub fn parse_date(date: &str) -> Option<u64> {
+    let mut parts = date.trim().splitn(3, '-').map(|x| x.parse::<u64>().ok());
+    let (year, month, day) = (parts.next()??, parts.next()??, parts.next()??);
+    if year < 1970 || !(1..=12).contains(&month) || day == 0 {
+        return None;
+    }
+
+    // Counting from March puts the leap day at the end of the year, as in civil_date
+    let year_from_march = if month <= 2 { year - 1 } else { year };
+    let era = year_from_march / 400;
+    let year_of_era = year_from_march % 400;
+    let month_index = (month + 9) % 12;
+    // Years and days too large to be a time in seconds are rejected rather than overflowing
+    let day_of_year = ((153 * month_index + 2) / 5).checked_add(day - 1)?;
+    let day_of_era =
+        (year_of_era * 365 + year_of_era / 4 - year_of_era / 100).checked_add(day_of_year)?;
+    let days = era
+        .checked_mul(146_097)?
+        .checked_add(day_of_era)?
+        .checked_sub(719_468)?;
+
+    // Days past the end of the month roll over into the next one, so they're rejected
+    if civil_date(days) != (year, month, day) {
+        return None;
+    }
+    days.checked_mul(DAY)
+}
+
+/// Converts days since the Unix epoch into a year, month and day, using Howard Hinnant's
+/// `civil_from_days` algorithm
+fn civil_date(days: u64) -> (u64, u64, u64) {
+    // Counting from 0000-03-01 puts leap days at the end of each 400 year era
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,120 @@
+//! The trash, which holds accounts that have been removed from the vault. Trashed accounts are
+//! saved in the vault file like any other account, encrypted, with the time they were trashed in
+//! their metadata, and are kept apart from the vault's accounts while the app is running. They're
+//! purged for good once they've been in the trash for the configured number of days
+
+use crate::account::Account;
+use crate::time::DAY;
+
+/// Splits the accounts read from the vault file into the vault's accounts and the trash
+///
+/// # Arguments
+///
+/// * `accounts` - Every account read from the vault file
+///
+/// # Returns
+///
+/// The accounts that aren't in the trash, then the trashed accounts, each in their saved order
+pub fn split_trash(accounts: Vec<Account>) -> (Vec<Account>, Vec<Account>) {
+    accounts
+        .into_iter()
+        .partition(|account| account.trashed().is_none())
+}
+
+/// Moves every temporary account whose time is up to the trash
+///
+/// # Arguments
+///
+/// * `accounts` - Every account read from the vault file
+/// * `now`      - The current time, in seconds since the Unix epoch
+///
+/// # Returns
+///
+/// The positions of the accounts that were moved
+///
+/// # Example
+///
+/// ```
+/// # use password_manager::account::Account;
+/// # use password_manager::trash::{split_trash, trash_expired};
+/// let mut accounts = vec![
+///     Account::builder().label("contractor").build(),
+///     Account::builder().label("mine").build(),
+/// ];
+/// accounts[0].set_deletes_on(Some(100));
+///
+/// assert_eq!(trash_expired(&mut accounts, 99), vec![]);
+/// assert_eq!(trash_expired(&mut accounts, 100), vec![0]);
+///
+/// let (accounts, trash) = split_trash(accounts);
+/// assert_eq!(accounts[0].label(), "mine");
+/// assert_eq!(trash[0].label(), "contractor");
+/// ```
+pub fn trash_expired(accounts: &mut [Account], now: u64) -> Vec<usize> {
+    let mut trashed = vec![];
+    for (index, account) in accounts.iter_mut().enumerate() {
+        let expired = account
+            .deletes_on()
+            .is_some_and(|deletes_on| deletes_on <= now);
+        if expired && account.trashed().is_none() {
+            account.move_to_trash(now);
+            trashed.push(index);
+        }
+    }
+    trashed
+}
+
+/// Returns when a trashed account is purged for good
+///
+/// # Arguments
+///
+/// * `account`   - The trashed account
+/// * `retention` - How many days accounts are kept in the trash, or 0 to keep them forever
+///
+/// # Returns
+///
+/// `None` if the account isn't in the trash or is kept forever, otherwise the time in seconds
+/// since the Unix epoch
+pub fn purged_on(account: &Account, retention: u64) -> Option<u64> {
+    if retention == 0 {
+        return None;
+    }
+    Some(
+        account
+            .trashed()?
+            .saturating_add(retention.saturating_mul(DAY)),
+    )
+}
+
+/// Permanently removes every account that has been in the trash for longer than the retention
+/// period. Accounts that aren't in the trash are left alone
+///
+/// # Arguments
+///
+/// * `accounts`  - The accounts to purge from
+/// * `retention` - How many days accounts are kept in the trash, or 0 to keep them forever
+/// * `now`       - The current time, in seconds since the Unix epoch
+///
+/// # Returns
+///
+/// How many accounts were purged
+///
+/// # Example
+///
+/// ```
+/// # use password_manager::account::Account;
+/// # use password_manager::time::DAY;
+/// # use password_manager::trash::purge_expired;
+/// let mut accounts = vec![Account::builder().build(), Account::builder().build()];
+/// accounts[0].move_to_trash(0);
+///
+/// assert_eq!(purge_expired(&mut accounts, 30, 29 * DAY), 0);
+/// assert_eq!(purge_expired(&mut accounts, 0, 100 * DAY), 0);
+/// assert_eq!(purge_expired(&mut accounts, 30, 30 * DAY), 1);
+/// assert_eq!(accounts.len(), 1);
+/// ```
+pub fn purge_expired(accounts: &mut Vec<Account>, retention: u64, now: u64) -> usize {
+    let before = accounts.len();
+    accounts.retain(|account| purged_on(account, retention).is_none_or(|purged| purged > now));
+    before - accounts.len()
+}